@@ -0,0 +1,196 @@
+//! An asynchronous driver for use with `embedded-hal-async`, enabled by
+//! the `async` feature.
+//!
+//! `Drv2605Async` mirrors the core of the blocking `Drv2605` API.  The
+//! register types, `Effect`s and `DrvError` are shared with the blocking
+//! driver; only the transport differs.
+
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::I2c;
+
+use crate::{
+    input_config_valid, mode_uses_input, Control1Reg, Control3Reg, DrvError, Effect, GoReg,
+    LibrarySelection, Mode, ModeReg, Register, RegisterThree, RtpFormat, StatusReg, WaveformReg,
+    ADDRESS,
+};
+
+/// An asynchronous DRV2605 driver; see the blocking `Drv2605` for details
+pub struct Drv2605Async<I2C> {
+    i2c: I2C,
+}
+
+impl<I2C: I2c> Drv2605Async<I2C> {
+    /// Construct a driver instance, but don't do any initialization
+    pub fn new(i2c: I2C) -> Self {
+        Self { i2c }
+    }
+
+    /// Consumes the driver and returns the I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Write `value` to `register`
+    async fn write(&mut self, register: Register, value: u8) -> Result<(), DrvError<I2C::Error>> {
+        self.i2c
+            .write(ADDRESS, &[register as u8, value])
+            .await
+            .map_err(DrvError::I2c)
+    }
+
+    /// Read an 8-bit value from the register
+    async fn read(&mut self, register: Register) -> Result<u8, DrvError<I2C::Error>> {
+        let mut buf = [0u8; 1];
+        self.i2c
+            .write_read(ADDRESS, &[register as u8], &mut buf)
+            .await
+            .map_err(DrvError::I2c)?;
+        Ok(buf[0])
+    }
+
+    /// Reads the status register
+    pub async fn get_status(&mut self) -> Result<StatusReg, DrvError<I2C::Error>> {
+        self.read(Register::Status).await.map(StatusReg)
+    }
+
+    /// Reads the mode register, including the standby bit
+    pub async fn get_mode(&mut self) -> Result<ModeReg, DrvError<I2C::Error>> {
+        self.read(Register::Mode).await.map(ModeReg)
+    }
+
+    /// Put the device into standby mode, or wake it up from standby
+    pub async fn set_standby(&mut self, standby: bool) -> Result<(), DrvError<I2C::Error>> {
+        let mut mode = ModeReg(self.read(Register::Mode).await?);
+        mode.set_standby(standby);
+        self.write(Register::Mode, mode.0).await
+    }
+
+    /// Select the operating `Mode`.  The standby bit is left unchanged.
+    /// Returns `DrvError::InvalidConfig` if the input configuration can't
+    /// produce output in `mode`; see `Drv2605::set_input_signal`.
+    pub async fn set_mode(&mut self, mode: Mode) -> Result<(), DrvError<I2C::Error>> {
+        if mode_uses_input(mode) {
+            let control1 = Control1Reg(self.read(Register::Control1).await?);
+            let control3 = Control3Reg(self.read(Register::Control3).await?);
+            if !input_config_valid(mode, control1, control3) {
+                return Err(DrvError::InvalidConfig);
+            }
+        }
+        let mut register = ModeReg(self.read(Register::Mode).await?);
+        register.set_mode(mode as u8);
+        self.write(Register::Mode, register.0).await
+    }
+
+    /// Selects the library the playback engine selects when the GO bit is set.
+    pub async fn set_library(
+        &mut self,
+        value: LibrarySelection,
+    ) -> Result<(), DrvError<I2C::Error>> {
+        let mut register = RegisterThree(self.read(Register::Register3).await?);
+        register.set_library_selection(value as u8);
+        self.write(Register::Register3, register.0).await
+    }
+
+    /// Sets the waveform generation registers to the shape provided
+    pub async fn set_waveform(
+        &mut self,
+        waveform: &[WaveformReg; 8],
+    ) -> Result<(), DrvError<I2C::Error>> {
+        let buf: [u8; 9] = [
+            Register::WaveformSequence0 as u8,
+            waveform[0].0,
+            waveform[1].0,
+            waveform[2].0,
+            waveform[3].0,
+            waveform[4].0,
+            waveform[5].0,
+            waveform[6].0,
+            waveform[7].0,
+        ];
+        self.i2c.write(ADDRESS, &buf).await.map_err(DrvError::I2c)
+    }
+
+    /// Programs `effect` into the first sequencer slot, followed by a stop
+    pub async fn set_single_effect(&mut self, effect: Effect) -> Result<(), DrvError<I2C::Error>> {
+        let buf: [u8; 3] = [
+            Register::WaveformSequence0 as u8,
+            WaveformReg::new_effect(effect).0,
+            WaveformReg::new_stop().0,
+        ];
+        self.i2c.write(ADDRESS, &buf).await.map_err(DrvError::I2c)
+    }
+
+    /// Reads the GO register, which stays set while playback is in progress
+    pub async fn get_go(&mut self) -> Result<GoReg, DrvError<I2C::Error>> {
+        self.read(Register::Go).await.map(GoReg)
+    }
+
+    /// Sets or clears the GO bit; see `Drv2605::set_go`
+    pub async fn set_go(&mut self, go: bool) -> Result<(), DrvError<I2C::Error>> {
+        let mut register = GoReg(self.read(Register::Go).await?);
+        register.set_go(go);
+        self.write(Register::Go, register.0).await
+    }
+
+    /// Waits until the process fired by the GO bit completes, polling the
+    /// GO bit once per millisecond and returning `DrvError::Timeout` if it
+    /// is still set after `timeout_ms`.
+    pub async fn wait_until_idle<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u16,
+    ) -> Result<(), DrvError<I2C::Error>> {
+        let mut elapsed = 0;
+        while self.get_go().await?.go() {
+            if elapsed >= timeout_ms {
+                return Err(DrvError::Timeout);
+            }
+            delay.delay_ms(1).await;
+            elapsed += 1;
+        }
+        Ok(())
+    }
+
+    /// Configures and starts real-time playback in one call; see
+    /// `Drv2605::start_rtp`.
+    pub async fn start_rtp(
+        &mut self,
+        format: RtpFormat,
+        initial_duty: u8,
+    ) -> Result<(), DrvError<I2C::Error>> {
+        let mut control3 = Control3Reg(self.read(Register::Control3).await?);
+        control3.set_data_format_rtp(match format {
+            RtpFormat::Signed => false,
+            RtpFormat::Unsigned => true,
+        });
+        self.write(Register::Control3, control3.0).await?;
+        self.write(Register::RealTimePlaybackInput, initial_duty)
+            .await?;
+
+        let mut mode = ModeReg(self.read(Register::Mode).await?);
+        mode.set_standby(false);
+        mode.set_mode(Mode::RealTimePlayback as u8);
+        self.write(Register::Mode, mode.0).await
+    }
+
+    /// Sets the unsigned real-time playback duty, returning
+    /// `DrvError::FormatMismatch` if the device is configured for
+    /// `RtpFormat::Signed`; see `Drv2605::set_rtp`.
+    pub async fn set_rtp(&mut self, duty: u8) -> Result<(), DrvError<I2C::Error>> {
+        if !Control3Reg(self.read(Register::Control3).await?).data_format_rtp() {
+            return Err(DrvError::FormatMismatch);
+        }
+        self.write(Register::RealTimePlaybackInput, duty).await
+    }
+
+    /// Sets the signed real-time playback value, returning
+    /// `DrvError::FormatMismatch` if the device is configured for
+    /// `RtpFormat::Unsigned`; see `Drv2605::set_rtp_signed`.
+    pub async fn set_rtp_signed(&mut self, value: i8) -> Result<(), DrvError<I2C::Error>> {
+        if Control3Reg(self.read(Register::Control3).await?).data_format_rtp() {
+            return Err(DrvError::FormatMismatch);
+        }
+        self.write(Register::RealTimePlaybackInput, value as u8)
+            .await
+    }
+}
@@ -0,0 +1,150 @@
+//! Coordinated control of two actuators, such as the left and right sides
+//! of a wearable.
+//!
+//! Every DRV2605 answers at the fixed address `0x5a`, so two devices can't
+//! share one I2C bus directly.  Put each on its own bus (or behind an I2C
+//! multiplexer, or through separate shared-bus handles that switch between
+//! physical buses), construct a `Drv2605` for each, and combine them:
+//!
+//! ```no_run
+//! # extern crate drv2605;
+//! # extern crate embedded_hal;
+//! # use embedded_hal::blocking::i2c::{Write, WriteRead};
+//! # struct Bus;
+//! # impl Write for Bus {
+//! #     type Error = ();
+//! #     fn write(&mut self, _: u8, _: &[u8]) -> Result<(), ()> { Ok(()) }
+//! # }
+//! # impl WriteRead for Bus {
+//! #     type Error = ();
+//! #     fn write_read(&mut self, _: u8, _: &[u8], _: &mut [u8]) -> Result<(), ()> { Ok(()) }
+//! # }
+//! # let (left_i2c, right_i2c) = (Bus, Bus);
+//! use drv2605::dual::{DualError, DualHaptic};
+//! use drv2605::{Drv2605, Effect};
+//!
+//! let left = Drv2605::new(left_i2c);
+//! let right = Drv2605::new(right_i2c);
+//! let mut pair = DualHaptic::new(left, right);
+//! if let Err(DualError::Right(err)) = pair.play_mirrored(Effect::StrongClick100) {
+//!     // only the right-hand bus failed
+//! #   let _ = err;
+//! }
+//! ```
+//!
+//! The two buses may be different types with different error types;
+//! failures are reported as a `DualError` that says which side they came
+//! from.
+//!
+//! Both devices must already be initialized and in `Mode::InternalTrigger`.
+//! The helpers program both sequencers before setting either GO bit, so
+//! the two effects start within one register write of each other.
+
+use hal::blocking::i2c::{Write, WriteRead};
+
+use crate::{Drv2605, DrvError, Effect};
+
+/// An error from one side of a `DualHaptic`
+#[derive(Debug)]
+pub enum DualError<EL, ER> {
+    /// The left-hand driver failed
+    Left(DrvError<EL>),
+    /// The right-hand driver failed
+    Right(DrvError<ER>),
+}
+
+/// Which of the two actuators to address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The actuator driven by the first driver passed to `DualHaptic::new`
+    Left,
+    /// The actuator driven by the second driver passed to `DualHaptic::new`
+    Right,
+}
+
+/// Two drivers addressed together; see the module docs
+pub struct DualHaptic<L, R>
+where
+    L: WriteRead + Write,
+    R: WriteRead + Write,
+{
+    left: Drv2605<L>,
+    right: Drv2605<R>,
+}
+
+impl<L, R, EL, ER> DualHaptic<L, R>
+where
+    L: WriteRead<Error = EL> + Write<Error = EL>,
+    R: WriteRead<Error = ER> + Write<Error = ER>,
+{
+    /// Pairs two drivers, which should already be initialized
+    pub fn new(left: Drv2605<L>, right: Drv2605<R>) -> Self {
+        Self { left, right }
+    }
+
+    /// Consumes the pair and returns the two drivers
+    pub fn release(self) -> (Drv2605<L>, Drv2605<R>) {
+        (self.left, self.right)
+    }
+
+    /// Returns the left-hand driver, for settings that differ per side
+    pub fn left(&mut self) -> &mut Drv2605<L> {
+        &mut self.left
+    }
+
+    /// Returns the right-hand driver, for settings that differ per side
+    pub fn right(&mut self) -> &mut Drv2605<R> {
+        &mut self.right
+    }
+
+    /// Plays the same effect on both actuators
+    pub fn play_mirrored(&mut self, effect: Effect) -> Result<(), DualError<EL, ER>> {
+        self.play_pair(effect, effect)
+    }
+
+    /// Plays a different effect on each actuator, such as a strong click
+    /// on one side and a soft bump on the other
+    pub fn play_complementary(
+        &mut self,
+        left: Effect,
+        right: Effect,
+    ) -> Result<(), DualError<EL, ER>> {
+        self.play_pair(left, right)
+    }
+
+    /// Plays `effect` on one actuator only, cancelling anything playing on
+    /// the other so that the pattern is clearly one-sided
+    pub fn play_on(&mut self, side: Side, effect: Effect) -> Result<(), DualError<EL, ER>> {
+        match side {
+            Side::Left => {
+                self.right.set_go(false).map_err(DualError::Right)?;
+                self.left
+                    .set_single_effect(effect)
+                    .map_err(DualError::Left)?;
+                self.left.set_go(true).map_err(DualError::Left)
+            }
+            Side::Right => {
+                self.left.set_go(false).map_err(DualError::Left)?;
+                self.right
+                    .set_single_effect(effect)
+                    .map_err(DualError::Right)?;
+                self.right.set_go(true).map_err(DualError::Right)
+            }
+        }
+    }
+
+    /// Cancels playback on both actuators
+    pub fn stop(&mut self) -> Result<(), DualError<EL, ER>> {
+        self.left.set_go(false).map_err(DualError::Left)?;
+        self.right.set_go(false).map_err(DualError::Right)
+    }
+
+    fn play_pair(&mut self, left: Effect, right: Effect) -> Result<(), DualError<EL, ER>> {
+        self.left.set_single_effect(left).map_err(DualError::Left)?;
+        self.right
+            .set_single_effect(right)
+            .map_err(DualError::Right)?;
+        self.left.replay().map_err(DualError::Left)?;
+        self.right.replay().map_err(DualError::Right)
+    }
+}
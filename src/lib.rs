@@ -7,9 +7,18 @@ extern crate embedded_hal as hal;
 #[macro_use]
 extern crate bitfield;
 
+pub mod dual;
+pub mod well_known;
+
+#[cfg(feature = "async")]
+pub mod asynch;
+
+use core::convert::TryFrom;
+use hal::blocking::delay::DelayMs;
 use hal::blocking::i2c::{Write, WriteRead};
 
 bitfield!{
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct StatusReg(u8);
     impl Debug;
     /// Latching overcurrent detection flag.  If the load impedance is below
@@ -48,7 +57,119 @@ bitfield!{
     pub device_id, _: 7, 5;
 }
 
+/// The part number reported in `StatusReg::device_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceVariant {
+    /// Contains RAM, does not contain the licensed ROM library
+    Drv2604 = 4,
+    /// Contains the licensed ROM library, does not contain RAM
+    Drv2605 = 3,
+    /// Low-voltage version of the DRV2604
+    Drv2604L = 6,
+    /// Low-voltage version of the DRV2605
+    Drv2605L = 7,
+}
+
+impl DeviceVariant {
+    /// Returns true if the part contains the licensed ROM effect libraries
+    pub fn has_rom(self) -> bool {
+        matches!(self, DeviceVariant::Drv2605 | DeviceVariant::Drv2605L)
+    }
+
+    /// Returns true if the part contains RAM for custom waveforms
+    pub fn has_ram(self) -> bool {
+        matches!(self, DeviceVariant::Drv2604 | DeviceVariant::Drv2604L)
+    }
+
+    /// Returns true for the low-voltage "L" versions of the parts
+    pub fn low_voltage(self) -> bool {
+        matches!(self, DeviceVariant::Drv2604L | DeviceVariant::Drv2605L)
+    }
+}
+
+impl TryFrom<u8> for DeviceVariant {
+    type Error = u8;
+
+    /// Converts a DEVICE_ID value to the `DeviceVariant`, returning the
+    /// value back as the error if it isn't a known part.
+    fn try_from(val: u8) -> Result<DeviceVariant, u8> {
+        match val {
+            3 => Ok(DeviceVariant::Drv2605),
+            4 => Ok(DeviceVariant::Drv2604),
+            6 => Ok(DeviceVariant::Drv2604L),
+            7 => Ok(DeviceVariant::Drv2605L),
+            _ => Err(val),
+        }
+    }
+}
+
+/// Identification of whichever DRV260x part responded, as returned by
+/// `Drv2605::identify`.  The capability flags are all false if the
+/// DEVICE_ID isn't a known part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChipInfo {
+    /// The raw DEVICE_ID[2:0] value
+    pub device_id: u8,
+    /// The decoded part, if DEVICE_ID is one of the known values
+    pub variant: Option<DeviceVariant>,
+    pub has_rom: bool,
+    pub has_ram: bool,
+    pub low_voltage: bool,
+}
+
+impl From<u8> for ChipInfo {
+    /// Decodes a DEVICE_ID value
+    fn from(device_id: u8) -> Self {
+        let variant = DeviceVariant::try_from(device_id).ok();
+        ChipInfo {
+            device_id,
+            variant,
+            has_rom: matches!(variant, Some(v) if v.has_rom()),
+            has_ram: matches!(variant, Some(v) if v.has_ram()),
+            low_voltage: matches!(variant, Some(v) if v.low_voltage()),
+        }
+    }
+}
+
+/// A latched fault that shuts the output down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    OverCurrent,
+    OverTemperature,
+}
+
+/// The overall health reported by the status register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusSummary {
+    /// No faults are latched and the last routine passed
+    Healthy,
+    /// The device has shut the output down
+    Fault(FaultKind),
+    /// The last auto-calibration or diagnostic routine failed; the two
+    /// share a result flag, so which one it was depends on the mode
+    RoutineFailed,
+}
+
+impl StatusReg {
+    /// Classifies the status flags into a single health state.  Faults
+    /// take precedence over a failed routine, and over-current over
+    /// over-temperature.  The feedback controller time-out flag is for
+    /// debugging and may be set during normal operation, so it is ignored.
+    pub fn summary(&self) -> StatusSummary {
+        if self.oc_detected() {
+            StatusSummary::Fault(FaultKind::OverCurrent)
+        } else if self.over_temp() {
+            StatusSummary::Fault(FaultKind::OverTemperature)
+        } else if self.diagnostic_result() {
+            StatusSummary::RoutineFailed
+        } else {
+            StatusSummary::Healthy
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Mode {
     /// Waveforms are fired by setting the GO bit in register 0x0C.
     InternalTrigger = 0,
@@ -84,22 +205,33 @@ pub enum Mode {
     AutoCalibration = 7,
 }
 
-impl From<u8> for Mode {
-    fn from(val: u8) -> Mode {
+impl Mode {
+    /// Decodes MODE[2:0], returning the value back as the error if it is out
+    /// of range.  This isn't a `TryFrom<u8>` impl because the existing
+    /// `From<u8>` impl already provides one through the standard library's
+    /// blanket impl, and the two would conflict.
+    pub fn try_from_bits(val: u8) -> Result<Mode, u8> {
         match val {
-            0 => Mode::InternalTrigger,
-            1 => Mode::ExternalTriggerRisingEdge,
-            2 => Mode::ExternalTriggerLevelMode,
-            3 => Mode::PwmInputAndAnalogInput,
-            4 => Mode::AudioToVibe,
-            5 => Mode::RealTimePlayback,
-            6 => Mode::Diagnostics,
-            7 => Mode::AutoCalibration,
-            _ => unreachable!("impossible value read back from Mode register"),
+            0 => Ok(Mode::InternalTrigger),
+            1 => Ok(Mode::ExternalTriggerRisingEdge),
+            2 => Ok(Mode::ExternalTriggerLevelMode),
+            3 => Ok(Mode::PwmInputAndAnalogInput),
+            4 => Ok(Mode::AudioToVibe),
+            5 => Ok(Mode::RealTimePlayback),
+            6 => Ok(Mode::Diagnostics),
+            7 => Ok(Mode::AutoCalibration),
+            _ => Err(val),
         }
     }
 }
 
+impl From<u8> for Mode {
+    fn from(val: u8) -> Mode {
+        Mode::try_from_bits(val)
+            .unwrap_or_else(|_| unreachable!("impossible value read back from Mode register"))
+    }
+}
+
 bitfield!{
     pub struct ModeReg(u8);
     impl Debug;
@@ -118,7 +250,26 @@ bitfield!{
     pub into Mode, mode, set_mode: 2, 0;
 }
 
+impl ModeReg {
+    /// Like `mode`, but returns the raw MODE[2:0] value as the error if it
+    /// isn't a `Mode` instead of panicking
+    pub fn try_mode(&self) -> Result<Mode, u8> {
+        Mode::try_from_bits(self.0 & 0x07)
+    }
+}
+
+impl Default for ModeReg {
+    /// The power-on value: in standby, with `Mode::InternalTrigger` selected
+    fn default() -> Self {
+        let mut mode = ModeReg(0);
+        mode.set_standby(true);
+        mode.set_mode(Mode::InternalTrigger as u8);
+        mode
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum LibrarySelection {
     Empty = 0,
     A = 1,
@@ -130,22 +281,41 @@ pub enum LibrarySelection {
     Reserved = 7,
 }
 
-impl From<u8> for LibrarySelection {
-    fn from(val: u8) -> LibrarySelection {
+impl LibrarySelection {
+    /// Decodes LIBRARY_SEL[2:0], returning the value back as the error if it is out
+    /// of range.  As with `Mode::try_from_bits`, `From<u8>` rules out a
+    /// `TryFrom<u8>` impl.
+    pub fn try_from_bits(val: u8) -> Result<LibrarySelection, u8> {
         match val {
-            0 => LibrarySelection::Empty,
-            1 => LibrarySelection::A,
-            2 => LibrarySelection::B,
-            3 => LibrarySelection::C,
-            4 => LibrarySelection::D,
-            5 => LibrarySelection::E,
-            6 => LibrarySelection::LRA,
-            7 => LibrarySelection::Reserved,
-            _ => unreachable!("impossible LibrarySelection value"),
+            0 => Ok(LibrarySelection::Empty),
+            1 => Ok(LibrarySelection::A),
+            2 => Ok(LibrarySelection::B),
+            3 => Ok(LibrarySelection::C),
+            4 => Ok(LibrarySelection::D),
+            5 => Ok(LibrarySelection::E),
+            6 => Ok(LibrarySelection::LRA),
+            7 => Ok(LibrarySelection::Reserved),
+            _ => Err(val),
         }
     }
 }
 
+impl From<u8> for LibrarySelection {
+    fn from(val: u8) -> LibrarySelection {
+        LibrarySelection::try_from_bits(val)
+            .unwrap_or_else(|_| unreachable!("impossible LibrarySelection value"))
+    }
+}
+
+/// The kind of actuator connected to the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MotorType {
+    /// Eccentric rotating mass
+    Erm,
+    /// Linear resonant actuator
+    Lra,
+}
+
 bitfield!{
     pub struct RegisterThree(u8);
     impl Debug;
@@ -161,9 +331,18 @@ bitfield!{
     pub into LibrarySelection, library_selection, set_library_selection: 2, 0;
 }
 
+impl RegisterThree {
+    /// Like `library_selection`, but returns the raw LIBRARY_SEL[2:0] value
+    /// as the error if it isn't a `LibrarySelection` instead of panicking
+    pub fn try_library_selection(&self) -> Result<LibrarySelection, u8> {
+        LibrarySelection::try_from_bits(self.0 & 0x07)
+    }
+}
+
 /// Identifies which of the waveforms from the ROM library that should
 /// be played in a given waveform slot.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Effect {
     /// Strong Click - 100%
     StrongClick100 = 1,
@@ -413,7 +592,460 @@ pub enum Effect {
     SmoothHumFive10 = 123,
 }
 
+impl TryFrom<u8> for Effect {
+    type Error = u8;
+
+    /// Converts a library effect ID (1 to 123) to the `Effect`, returning
+    /// the ID back as the error if it isn't one.
+    fn try_from(val: u8) -> Result<Effect, u8> {
+        let effect = match val {
+            1 => Effect::StrongClick100,
+            2 => Effect::StrongClick60,
+            3 => Effect::StrongClick30,
+            4 => Effect::SharpClick100,
+            5 => Effect::SharpClick60,
+            6 => Effect::SharpClick30,
+            7 => Effect::SoftBump100,
+            8 => Effect::SoftBump60,
+            9 => Effect::SoftBump30,
+            10 => Effect::DoubleClick100,
+            11 => Effect::DoubleClick60,
+            12 => Effect::TripleClick100,
+            13 => Effect::SoftFuzz60,
+            14 => Effect::StrongBuzz100,
+            15 => Effect::Alert750ms,
+            16 => Effect::Alert1000ms,
+            17 => Effect::StrongClickOne100,
+            18 => Effect::StrongClickTwo80,
+            19 => Effect::StrongClickThree60,
+            20 => Effect::StrongClickFour30,
+            21 => Effect::MediumClickOne100,
+            22 => Effect::MediumClickTwo80,
+            23 => Effect::MediumClickThree60,
+            24 => Effect::SharpTickOne100,
+            25 => Effect::SharpTickTwo80,
+            26 => Effect::SharpTickThree60,
+            27 => Effect::ShortDoubleClickStrongOne100,
+            28 => Effect::ShortDoubleClickStrongTwo80,
+            29 => Effect::ShortDoubleClickStrongThree60,
+            30 => Effect::ShortDoubleClickStrongFour30,
+            31 => Effect::ShortDoubleClickMediumOne100,
+            32 => Effect::ShortDoubleClickMediumTwo80,
+            33 => Effect::ShortDoubleClickMediumThree60,
+            34 => Effect::ShortDoubleSharpTickOne100,
+            35 => Effect::ShortDoubleSharpTickTwo80,
+            36 => Effect::ShortDoubleSharpTickThree60,
+            37 => Effect::LongDoubleSharpClickStrongOne100,
+            38 => Effect::LongDoubleSharpClickStrongTwo80,
+            39 => Effect::LongDoubleSharpClickStrongThree60,
+            40 => Effect::LongDoubleSharpClickStrongFour30,
+            41 => Effect::LongDoubleSharpClickMediumOne100,
+            42 => Effect::LongDoubleSharpClickMediumTwo80,
+            43 => Effect::LongDoubleSharpClickMediumThree60,
+            44 => Effect::LongDoubleSharpTickOne100,
+            45 => Effect::LongDoubleSharpTickTwo80,
+            46 => Effect::LongDoubleSharpTickThree60,
+            47 => Effect::BuzzOne100,
+            48 => Effect::BuzzTwo80,
+            49 => Effect::BuzzThree60,
+            50 => Effect::BuzzFour40,
+            51 => Effect::BuzzFive20,
+            52 => Effect::PulsingStrongOne100,
+            53 => Effect::PulsingStrongTwo60,
+            54 => Effect::PulsingMediumOne100,
+            55 => Effect::PulsingMediumTwo60,
+            56 => Effect::PulsingSharpOne100,
+            57 => Effect::PulsingSharpTwo60,
+            58 => Effect::TransitionClickOne100,
+            59 => Effect::TransitionClickTwo80,
+            60 => Effect::TransitionClickThree60,
+            61 => Effect::TransitionClickFour40,
+            62 => Effect::TransitionClickFive20,
+            63 => Effect::TransitionClickSix10,
+            64 => Effect::TransitionHumOne100,
+            65 => Effect::TransitionHumTwo80,
+            66 => Effect::TransitionHumThree60,
+            67 => Effect::TransitionHumFour40,
+            68 => Effect::TransitionHumFive20,
+            69 => Effect::TransitionHumSix10,
+            70 => Effect::TransitionRampDownLongSmoothOne100to0,
+            71 => Effect::TransitionRampDownLongSmoothTwo100to0,
+            72 => Effect::TransitionRampDownMediumSmoothOne100to0,
+            73 => Effect::TransitionRampDownMediumSmoothTwo100to0,
+            74 => Effect::TransitionRampDownShortSmoothOne100to0,
+            75 => Effect::TransitionRampDownShortSmoothTwo100to0,
+            76 => Effect::TransitionRampDownLongSharpOne100to0,
+            77 => Effect::TransitionRampDownLongSharpTwo100to0,
+            78 => Effect::TransitionRampDownMediumSharpOne100to0,
+            79 => Effect::TransitionRampDownMediumSharpTwo100to0,
+            80 => Effect::TransitionRampDownShortSharpOne100to0,
+            81 => Effect::TransitionRampDownShortSharpTwo100to0,
+            82 => Effect::TransitionRampUpLongSmoothOne0to100,
+            83 => Effect::TransitionRampUpLongSmoothTwo0to100,
+            84 => Effect::TransitionRampUpMediumSmoothOne0to100,
+            85 => Effect::TransitionRampUpMediumSmoothTwo0to100,
+            86 => Effect::TransitionRampUpShortSmoothOne0to100,
+            87 => Effect::TransitionRampUpShortSmoothTwo0to100,
+            88 => Effect::TransitionRampUpLongSharpOne0to100,
+            89 => Effect::TransitionRampUpLongSharpTwo0to100,
+            90 => Effect::TransitionRampUpMediumSharpOne0to100,
+            91 => Effect::TransitionRampUpMediumSharpTwo0to100,
+            92 => Effect::TransitionRampUpShortSharpOne0to100,
+            93 => Effect::TransitionRampUpShortSharpTwo0to100,
+            94 => Effect::TransitionRampDownLongSmoothOne50to0,
+            95 => Effect::TransitionRampDownLongSmoothTwo50to0,
+            96 => Effect::TransitionRampDownMediumSmoothOne50to0,
+            97 => Effect::TransitionRampDownMediumSmoothTwo50to0,
+            98 => Effect::TransitionRampDownShortSmoothOne50to0,
+            99 => Effect::TransitionRampDownShortSmoothTwo50to0,
+            100 => Effect::TransitionRampDownLongSharpOne50to0,
+            101 => Effect::TransitionRampDownLongSharpTwo50to0,
+            102 => Effect::TransitionRampDownMediumSharpOne50to0,
+            103 => Effect::TransitionRampDownMediumSharpTwo50to0,
+            104 => Effect::TransitionRampDownShortSharpOne50to0,
+            105 => Effect::TransitionRampDownShortSharpTwo50to0,
+            106 => Effect::TransitionRampUpLongSmoothOne0to50,
+            107 => Effect::TransitionRampUpLongSmoothTwo0to50,
+            108 => Effect::TransitionRampUpMediumSmoothOne0to50,
+            109 => Effect::TransitionRampUpMediumSmoothTwo0to50,
+            110 => Effect::TransitionRampUpShortSmoothOne0to50,
+            111 => Effect::TransitionRampUpShortSmoothTwo0to50,
+            112 => Effect::TransitionRampUpLongSharpOne0to50,
+            113 => Effect::TransitionRampUpLongSharpTwo0to50,
+            114 => Effect::TransitionRampUpMediumSharpOne0to50,
+            115 => Effect::TransitionRampUpMediumSharpTwo0to50,
+            116 => Effect::TransitionRampUpShortSharpOne0to50,
+            117 => Effect::TransitionRampUpShortSharpTwo0to50,
+            118 => Effect::LongBuzzForProgrammaticStopping100,
+            119 => Effect::SmoothHumOne50,
+            120 => Effect::SmoothHumTwo40,
+            121 => Effect::SmoothHumThree30,
+            122 => Effect::SmoothHumFour20,
+            123 => Effect::SmoothHumFive10,
+            _ => return Err(val),
+        };
+        Ok(effect)
+    }
+}
+
+/// A decoded waveform sequencer slot
+#[derive(Debug, Clone, Copy)]
+pub enum SequenceEntry {
+    /// Play a library effect
+    Effect(Effect),
+    /// Wait for the given number of 10 ms intervals
+    Wait(u8),
+    /// An effect ID that isn't in the library
+    Unknown(u8),
+}
+
+/// The nominal strength to select with `Effect::at_intensity`
+#[derive(Debug, Clone, Copy)]
+pub enum IntensityLevel {
+    /// 100%
+    Full = 100,
+    /// 80%
+    High = 80,
+    /// 60%
+    Medium = 60,
+    /// 30%
+    Low = 30,
+    /// 10%
+    Minimum = 10,
+}
+
+/// Whether a transition ramp increases or decreases the drive
+#[derive(Debug, Clone, Copy)]
+pub enum RampDirection {
+    Up,
+    Down,
+}
+
+/// How long a transition ramp takes
+#[derive(Debug, Clone, Copy)]
+pub enum RampSpeed {
+    Long,
+    Medium,
+    Short,
+}
+
+/// The span of drive strength covered by a transition ramp
+#[derive(Debug, Clone, Copy)]
+pub enum RampRange {
+    /// Between 0 and 100%
+    Full,
+    /// Between 0 and 50%
+    Half,
+}
+
+impl Effect {
+    /// Returns the approximate playback time of the effect in
+    /// milliseconds, as a rough guide for scheduling.  The values are
+    /// estimates for each family of effects in the library, assuming the
+    /// default 5 ms PLAYBACK_INTERVAL; the actual time depends on the
+    /// library, motor and feedback settings (see
+    /// `Drv2605::play_sequence_timed` to measure it).
+    /// `LongBuzzForProgrammaticStopping100` is meant to be cancelled and
+    /// is counted as one second.
+    pub fn approx_duration_ms(self) -> u16 {
+        match self as u8 {
+            // Strong and sharp clicks
+            1..=6 => 60,
+            // Soft bumps
+            7..=9 => 100,
+            10 | 11 => 200,
+            12 => 300,
+            13 => 250,
+            14 => 300,
+            15 => 750,
+            16 => 1000,
+            // Strong and medium clicks
+            17..=23 => 60,
+            // Sharp ticks
+            24..=26 => 30,
+            // Short double clicks and ticks
+            27..=36 => 150,
+            // Long double clicks and ticks
+            37..=46 => 300,
+            // Buzzes
+            47..=51 => 300,
+            // Pulsing
+            52..=57 => 600,
+            // Transition clicks
+            58..=63 => 50,
+            // Transition hums
+            64..=69 => 200,
+            // Ramps come in long, medium and short pairs
+            70..=117 => match (self as u8 - 70) % 6 {
+                0 | 1 => 1000,
+                2 | 3 => 500,
+                _ => 250,
+            },
+            118 => 1000,
+            // Smooth hums
+            _ => 500,
+        }
+    }
+
+    /// Returns the smooth transition ramp matching the given parameters,
+    /// for example `TransitionRampUpMediumSmoothOne0to100` for
+    /// `(RampDirection::Up, RampSpeed::Medium, RampRange::Full)`.
+    pub fn transition_ramp(direction: RampDirection, speed: RampSpeed, range: RampRange) -> Effect {
+        match (direction, range, speed) {
+            (RampDirection::Down, RampRange::Full, RampSpeed::Long) => {
+                Effect::TransitionRampDownLongSmoothOne100to0
+            }
+            (RampDirection::Down, RampRange::Full, RampSpeed::Medium) => {
+                Effect::TransitionRampDownMediumSmoothOne100to0
+            }
+            (RampDirection::Down, RampRange::Full, RampSpeed::Short) => {
+                Effect::TransitionRampDownShortSmoothOne100to0
+            }
+            (RampDirection::Up, RampRange::Full, RampSpeed::Long) => {
+                Effect::TransitionRampUpLongSmoothOne0to100
+            }
+            (RampDirection::Up, RampRange::Full, RampSpeed::Medium) => {
+                Effect::TransitionRampUpMediumSmoothOne0to100
+            }
+            (RampDirection::Up, RampRange::Full, RampSpeed::Short) => {
+                Effect::TransitionRampUpShortSmoothOne0to100
+            }
+            (RampDirection::Down, RampRange::Half, RampSpeed::Long) => {
+                Effect::TransitionRampDownLongSmoothOne50to0
+            }
+            (RampDirection::Down, RampRange::Half, RampSpeed::Medium) => {
+                Effect::TransitionRampDownMediumSmoothOne50to0
+            }
+            (RampDirection::Down, RampRange::Half, RampSpeed::Short) => {
+                Effect::TransitionRampDownShortSmoothOne50to0
+            }
+            (RampDirection::Up, RampRange::Half, RampSpeed::Long) => {
+                Effect::TransitionRampUpLongSmoothOne0to50
+            }
+            (RampDirection::Up, RampRange::Half, RampSpeed::Medium) => {
+                Effect::TransitionRampUpMediumSmoothOne0to50
+            }
+            (RampDirection::Up, RampRange::Half, RampSpeed::Short) => {
+                Effect::TransitionRampUpShortSmoothOne0to50
+            }
+        }
+    }
+
+    /// Returns the variant of this effect from the same family (for example
+    /// `StrongClick100`, `StrongClick60` and `StrongClick30`) whose strength
+    /// is closest to `level`.  Effects that only come in one strength are
+    /// returned unchanged.
+    pub fn at_intensity(&self, level: IntensityLevel) -> Effect {
+        let family = match self.intensity_family() {
+            Some(family) => family,
+            None => return *self,
+        };
+        let target = level as u8;
+        let mut best = family[0];
+        for &candidate in family.iter() {
+            if candidate.0.abs_diff(target) < best.0.abs_diff(target) {
+                best = candidate;
+            }
+        }
+        best.1
+    }
+
+    /// The strength (in percent) and effect of each member of the family
+    /// that this effect belongs to
+    fn intensity_family(&self) -> Option<&'static [(u8, Effect)]> {
+        match *self {
+            Effect::StrongClick100 | Effect::StrongClick60 | Effect::StrongClick30 => Some(&[
+                (100, Effect::StrongClick100),
+                (60, Effect::StrongClick60),
+                (30, Effect::StrongClick30),
+            ]),
+            Effect::SharpClick100 | Effect::SharpClick60 | Effect::SharpClick30 => Some(&[
+                (100, Effect::SharpClick100),
+                (60, Effect::SharpClick60),
+                (30, Effect::SharpClick30),
+            ]),
+            Effect::SoftBump100 | Effect::SoftBump60 | Effect::SoftBump30 => Some(&[
+                (100, Effect::SoftBump100),
+                (60, Effect::SoftBump60),
+                (30, Effect::SoftBump30),
+            ]),
+            Effect::DoubleClick100 | Effect::DoubleClick60 => {
+                Some(&[(100, Effect::DoubleClick100), (60, Effect::DoubleClick60)])
+            }
+            Effect::StrongClickOne100
+            | Effect::StrongClickTwo80
+            | Effect::StrongClickThree60
+            | Effect::StrongClickFour30 => Some(&[
+                (100, Effect::StrongClickOne100),
+                (80, Effect::StrongClickTwo80),
+                (60, Effect::StrongClickThree60),
+                (30, Effect::StrongClickFour30),
+            ]),
+            Effect::MediumClickOne100 | Effect::MediumClickTwo80 | Effect::MediumClickThree60 => {
+                Some(&[
+                    (100, Effect::MediumClickOne100),
+                    (80, Effect::MediumClickTwo80),
+                    (60, Effect::MediumClickThree60),
+                ])
+            }
+            Effect::SharpTickOne100 | Effect::SharpTickTwo80 | Effect::SharpTickThree60 => Some(&[
+                (100, Effect::SharpTickOne100),
+                (80, Effect::SharpTickTwo80),
+                (60, Effect::SharpTickThree60),
+            ]),
+            Effect::ShortDoubleClickStrongOne100
+            | Effect::ShortDoubleClickStrongTwo80
+            | Effect::ShortDoubleClickStrongThree60
+            | Effect::ShortDoubleClickStrongFour30 => Some(&[
+                (100, Effect::ShortDoubleClickStrongOne100),
+                (80, Effect::ShortDoubleClickStrongTwo80),
+                (60, Effect::ShortDoubleClickStrongThree60),
+                (30, Effect::ShortDoubleClickStrongFour30),
+            ]),
+            Effect::ShortDoubleClickMediumOne100
+            | Effect::ShortDoubleClickMediumTwo80
+            | Effect::ShortDoubleClickMediumThree60 => Some(&[
+                (100, Effect::ShortDoubleClickMediumOne100),
+                (80, Effect::ShortDoubleClickMediumTwo80),
+                (60, Effect::ShortDoubleClickMediumThree60),
+            ]),
+            Effect::ShortDoubleSharpTickOne100
+            | Effect::ShortDoubleSharpTickTwo80
+            | Effect::ShortDoubleSharpTickThree60 => Some(&[
+                (100, Effect::ShortDoubleSharpTickOne100),
+                (80, Effect::ShortDoubleSharpTickTwo80),
+                (60, Effect::ShortDoubleSharpTickThree60),
+            ]),
+            Effect::LongDoubleSharpClickStrongOne100
+            | Effect::LongDoubleSharpClickStrongTwo80
+            | Effect::LongDoubleSharpClickStrongThree60
+            | Effect::LongDoubleSharpClickStrongFour30 => Some(&[
+                (100, Effect::LongDoubleSharpClickStrongOne100),
+                (80, Effect::LongDoubleSharpClickStrongTwo80),
+                (60, Effect::LongDoubleSharpClickStrongThree60),
+                (30, Effect::LongDoubleSharpClickStrongFour30),
+            ]),
+            Effect::LongDoubleSharpClickMediumOne100
+            | Effect::LongDoubleSharpClickMediumTwo80
+            | Effect::LongDoubleSharpClickMediumThree60 => Some(&[
+                (100, Effect::LongDoubleSharpClickMediumOne100),
+                (80, Effect::LongDoubleSharpClickMediumTwo80),
+                (60, Effect::LongDoubleSharpClickMediumThree60),
+            ]),
+            Effect::LongDoubleSharpTickOne100
+            | Effect::LongDoubleSharpTickTwo80
+            | Effect::LongDoubleSharpTickThree60 => Some(&[
+                (100, Effect::LongDoubleSharpTickOne100),
+                (80, Effect::LongDoubleSharpTickTwo80),
+                (60, Effect::LongDoubleSharpTickThree60),
+            ]),
+            Effect::BuzzOne100
+            | Effect::BuzzTwo80
+            | Effect::BuzzThree60
+            | Effect::BuzzFour40
+            | Effect::BuzzFive20 => Some(&[
+                (100, Effect::BuzzOne100),
+                (80, Effect::BuzzTwo80),
+                (60, Effect::BuzzThree60),
+                (40, Effect::BuzzFour40),
+                (20, Effect::BuzzFive20),
+            ]),
+            Effect::PulsingStrongOne100 | Effect::PulsingStrongTwo60 => Some(&[
+                (100, Effect::PulsingStrongOne100),
+                (60, Effect::PulsingStrongTwo60),
+            ]),
+            Effect::PulsingMediumOne100 | Effect::PulsingMediumTwo60 => Some(&[
+                (100, Effect::PulsingMediumOne100),
+                (60, Effect::PulsingMediumTwo60),
+            ]),
+            Effect::PulsingSharpOne100 | Effect::PulsingSharpTwo60 => Some(&[
+                (100, Effect::PulsingSharpOne100),
+                (60, Effect::PulsingSharpTwo60),
+            ]),
+            Effect::TransitionClickOne100
+            | Effect::TransitionClickTwo80
+            | Effect::TransitionClickThree60
+            | Effect::TransitionClickFour40
+            | Effect::TransitionClickFive20
+            | Effect::TransitionClickSix10 => Some(&[
+                (100, Effect::TransitionClickOne100),
+                (80, Effect::TransitionClickTwo80),
+                (60, Effect::TransitionClickThree60),
+                (40, Effect::TransitionClickFour40),
+                (20, Effect::TransitionClickFive20),
+                (10, Effect::TransitionClickSix10),
+            ]),
+            Effect::TransitionHumOne100
+            | Effect::TransitionHumTwo80
+            | Effect::TransitionHumThree60
+            | Effect::TransitionHumFour40
+            | Effect::TransitionHumFive20
+            | Effect::TransitionHumSix10 => Some(&[
+                (100, Effect::TransitionHumOne100),
+                (80, Effect::TransitionHumTwo80),
+                (60, Effect::TransitionHumThree60),
+                (40, Effect::TransitionHumFour40),
+                (20, Effect::TransitionHumFive20),
+                (10, Effect::TransitionHumSix10),
+            ]),
+            Effect::SmoothHumOne50
+            | Effect::SmoothHumTwo40
+            | Effect::SmoothHumThree30
+            | Effect::SmoothHumFour20
+            | Effect::SmoothHumFive10 => Some(&[
+                (50, Effect::SmoothHumOne50),
+                (40, Effect::SmoothHumTwo40),
+                (30, Effect::SmoothHumThree30),
+                (20, Effect::SmoothHumFour20),
+                (10, Effect::SmoothHumFive10),
+            ]),
+            _ => None,
+        }
+    }
+}
+
 bitfield!{
+    #[derive(Clone, Copy)]
     pub struct WaveformReg(u8);
     impl Debug;
     /// When this bit is set, the WAV_FRM_SEQ[6:0] bit is interpreted as a wait
@@ -422,7 +1054,7 @@ bitfield!{
     /// Delay time = 10 ms × WAV_FRM_SEQ[6:0]
     /// If WAIT = 0, then WAV_FRM_SEQ[6:0] is interpreted as a waveform
     /// identifier for sequence playback.
-    wait, set_wait: 7;
+    pub wait, set_wait: 7;
 
     /// Waveform sequence value. This bit holds the waveform identifier of the
     /// waveform to be played. A waveform identifier is an integer value referring
@@ -433,7 +1065,7 @@ bitfield!{
     /// identifier is non-zero. The waveform sequencer continues in this way until
     /// the sequencer reaches an identifier value of zero, or all eight identifiers are
     /// played (register addresses 0x04 through 0x0B), whichever comes first.
-    waveform_seq, set_waveform_seq: 6, 0;
+    pub waveform_seq, set_waveform_seq: 6, 0;
 }
 
 impl WaveformReg {
@@ -454,13 +1086,135 @@ impl WaveformReg {
     }
 
     /// Wait the specified amount of time (in 10ms intervals), before
-    /// moving to the next effect and playing it.
+    /// moving to the next effect and playing it.  The slot holds at most
+    /// 127 intervals (1.27 s); longer waits are clamped to that.
     pub fn new_wait_time(tens_of_ms: u8) -> Self {
         let mut w = WaveformReg(0);
         w.set_wait(true);
-        w.set_waveform_seq(tens_of_ms);
+        w.set_waveform_seq(tens_of_ms.min(0x7f));
         w
     }
+
+    /// Decodes the slot, returning `None` for the stop marker that ends
+    /// the sequence
+    pub fn entry(&self) -> Option<SequenceEntry> {
+        if self.wait() {
+            return Some(SequenceEntry::Wait(self.waveform_seq()));
+        }
+        match self.waveform_seq() {
+            0 => None,
+            id => Some(
+                Effect::try_from(id)
+                    .map(SequenceEntry::Effect)
+                    .unwrap_or(SequenceEntry::Unknown(id)),
+            ),
+        }
+    }
+}
+
+impl From<SequenceEntry> for WaveformReg {
+    fn from(entry: SequenceEntry) -> Self {
+        match entry {
+            SequenceEntry::Effect(effect) => WaveformReg::new_effect(effect),
+            SequenceEntry::Wait(tens_of_ms) => WaveformReg::new_wait_time(tens_of_ms),
+            SequenceEntry::Unknown(id) => WaveformReg(id & 0x7f),
+        }
+    }
+}
+
+/// Returned by `WaveformSequence` when an entry doesn't fit in the eight
+/// sequencer slots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceFull;
+
+/// Builds the contents of the eight waveform sequencer slots, padding
+/// the unused slots with stops; program it with `Drv2605::set_sequence`.
+///
+/// ```
+/// use drv2605::{Effect, WaveformSequence};
+///
+/// let mut seq = WaveformSequence::new();
+/// seq.push_effect(Effect::StrongClick100)
+///     .unwrap()
+///     .push_delay_ms(2000)
+///     .unwrap()
+///     .push_effect(Effect::StrongClick100)
+///     .unwrap();
+/// assert_eq!(seq.len(), 4);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct WaveformSequence {
+    slots: [WaveformReg; 8],
+    len: usize,
+}
+
+impl Default for WaveformSequence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaveformSequence {
+    pub fn new() -> Self {
+        Self {
+            slots: [WaveformReg::new_stop(); 8],
+            len: 0,
+        }
+    }
+
+    /// Returns the number of slots used so far
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the slots to program, with stops after the last entry
+    pub fn slots(&self) -> &[WaveformReg; 8] {
+        &self.slots
+    }
+
+    /// Returns a best-effort estimate of the playback time in
+    /// milliseconds; see `estimated_duration`
+    pub fn estimated_duration_ms(&self) -> u32 {
+        self.slots
+            .iter()
+            .map_while(WaveformReg::entry)
+            .map(|entry| estimated_duration(&[entry]))
+            .sum()
+    }
+
+    /// Appends an effect
+    pub fn push_effect(&mut self, effect: Effect) -> Result<&mut Self, SequenceFull> {
+        if self.len == self.slots.len() {
+            return Err(SequenceFull);
+        }
+        self.slots[self.len] = WaveformReg::new_effect(effect);
+        self.len += 1;
+        Ok(self)
+    }
+
+    /// Appends a wait of `ms` milliseconds, rounded to the nearest 10 ms.
+    /// A single slot waits at most 1270 ms, so longer delays use as many
+    /// slots as necessary.  Nothing is appended if they don't all fit.
+    pub fn push_delay_ms(&mut self, ms: u16) -> Result<&mut Self, SequenceFull> {
+        let mut remaining = (u32::from(ms) + 5) / 10;
+        // `u32::div_ceil` needs Rust 1.73
+        #[allow(clippy::manual_div_ceil)]
+        let needed = ((remaining + 126) / 127) as usize;
+        if self.len + needed > self.slots.len() {
+            return Err(SequenceFull);
+        }
+        while remaining > 0 {
+            let step = remaining.min(127);
+            self.slots[self.len] = WaveformReg::new_wait_time(step as u8);
+            self.len += 1;
+            remaining -= step;
+        }
+        Ok(self)
+    }
 }
 
 bitfield!{
@@ -479,7 +1233,134 @@ bitfield!{
     pub go, set_go: 0;
 }
 
+/// The peak detection time for the audio-to-vibe signal path
+#[derive(Debug, Clone, Copy)]
+pub enum PeakTime {
+    Ms10 = 0,
+    Ms20 = 1,
+    Ms30 = 2,
+    Ms40 = 3,
+}
+
+impl PeakTime {
+    /// Decodes ATH_PEAK_TIME[1:0], returning the value back as the error if it is out
+    /// of range
+    pub fn try_from_bits(val: u8) -> Result<PeakTime, u8> {
+        match val {
+            0 => Ok(PeakTime::Ms10),
+            1 => Ok(PeakTime::Ms20),
+            2 => Ok(PeakTime::Ms30),
+            3 => Ok(PeakTime::Ms40),
+            _ => Err(val),
+        }
+    }
+}
+
+impl From<u8> for PeakTime {
+    fn from(val: u8) -> PeakTime {
+        PeakTime::try_from_bits(val).unwrap_or_else(|_| unreachable!("impossible PeakTime value"))
+    }
+}
+
+/// The low-pass filter frequency for the audio-to-vibe signal path
+#[derive(Debug, Clone, Copy)]
+pub enum LpFilter {
+    Hz100 = 0,
+    Hz125 = 1,
+    Hz150 = 2,
+    Hz200 = 3,
+}
+
+impl LpFilter {
+    /// Decodes ATH_FILTER[1:0], returning the value back as the error if it is out
+    /// of range
+    pub fn try_from_bits(val: u8) -> Result<LpFilter, u8> {
+        match val {
+            0 => Ok(LpFilter::Hz100),
+            1 => Ok(LpFilter::Hz125),
+            2 => Ok(LpFilter::Hz150),
+            3 => Ok(LpFilter::Hz200),
+            _ => Err(val),
+        }
+    }
+}
+
+impl From<u8> for LpFilter {
+    fn from(val: u8) -> LpFilter {
+        LpFilter::try_from_bits(val).unwrap_or_else(|_| unreachable!("impossible LpFilter value"))
+    }
+}
+
+bitfield!{
+    #[derive(Clone, Copy, PartialEq)]
+    pub struct AudioToVibeControlReg(u8);
+    impl Debug;
+
+    /// This bit sets the peak detection time for the audio-to-vibe signal path.
+    pub into PeakTime, peak_time, set_peak_time: 3, 2;
+
+    /// This bit sets the low-pass filter frequency for the audio-to-vibe
+    /// signal path.
+    pub into LpFilter, filter, set_filter: 1, 0;
+}
+
+/// The audio-to-vibe settings, in the units taken by
+/// `Drv2605::configure_audio_to_vibe`
+#[derive(Debug, Clone, Copy)]
+pub struct AtvConfig {
+    /// The (minimum, maximum) input level in millivolts
+    pub input_range_mv: (u16, u16),
+    /// The (minimum, maximum) output drive as a percentage of full scale
+    pub output_range_percent: (u8, u8),
+    pub peak_time: PeakTime,
+    pub filter: LpFilter,
+}
+
+/// The feedback gain ratio between braking gain and driving gain.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub enum BrakeFactor {
+    X1 = 0,
+    X2 = 1,
+    X3 = 2,
+    X4 = 3,
+    X6 = 4,
+    X8 = 5,
+    X16 = 6,
+    /// Braking disabled.  Automatic braking only happens in closed-loop
+    /// operation; open-loop applications that generate their own brake
+    /// pulses can select this so that the feedback controller never adds
+    /// braking on top of them.
+    Disabled = 7,
+}
+
+impl BrakeFactor {
+    /// Decodes FB_BRAKE_FACTOR[2:0], returning the value back as the error if it is out
+    /// of range
+    pub fn try_from_bits(val: u8) -> Result<BrakeFactor, u8> {
+        match val {
+            0 => Ok(BrakeFactor::X1),
+            1 => Ok(BrakeFactor::X2),
+            2 => Ok(BrakeFactor::X3),
+            3 => Ok(BrakeFactor::X4),
+            4 => Ok(BrakeFactor::X6),
+            5 => Ok(BrakeFactor::X8),
+            6 => Ok(BrakeFactor::X16),
+            7 => Ok(BrakeFactor::Disabled),
+            _ => Err(val),
+        }
+    }
+}
+
+impl From<u8> for BrakeFactor {
+    fn from(val: u8) -> BrakeFactor {
+        BrakeFactor::try_from_bits(val)
+            .unwrap_or_else(|_| unreachable!("impossible BrakeFactor value"))
+    }
+}
+
 bitfield!{
+    #[derive(Clone, Copy, PartialEq)]
     pub struct FeedbackControlReg(u8);
     impl Debug;
 
@@ -503,7 +1384,7 @@ bitfield!{
     /// 5: 8x
     /// 6: 16x
     /// 7: Braking disabled
-    pub fb_brake_factor, set_fb_brake_factor: 6, 4;
+    pub into BrakeFactor, fb_brake_factor, set_fb_brake_factor: 6, 4;
 
     /// This bit selects a loop gain for the feedback control. The LOOP_GAIN[1:0] bit
     /// sets how fast the loop attempts to make the back-EMF (and thus motor velocity)
@@ -535,6 +1416,7 @@ bitfield!{
 }
 
 bitfield!{
+    #[derive(Clone, Copy, PartialEq)]
     pub struct Control1Reg(u8);
     impl Debug;
     /// This bit applies higher loop gain during overdrive to enhance actuator transient response.
@@ -559,6 +1441,7 @@ bitfield!{
 }
 
 bitfield!{
+    #[derive(Clone, Copy, PartialEq)]
     pub struct Control2Reg(u8);
     impl Debug;
     /// The BIDIR_INPUT bit selects how the engine interprets data.
@@ -606,7 +1489,138 @@ bitfield!{
     pub idiss_time, set_idiss_time: 1, 0;
 }
 
+/// The noise-gate threshold applied to PWM and analog inputs; input below
+/// the threshold produces no drive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseGate {
+    Disabled = 0,
+    Percent2 = 1,
+    Percent4 = 2,
+    Percent8 = 3,
+}
+
+impl NoiseGate {
+    /// Decodes NG_THRESH[1:0], returning the value back as the error if it is out
+    /// of range
+    pub fn try_from_bits(val: u8) -> Result<NoiseGate, u8> {
+        match val {
+            0 => Ok(NoiseGate::Disabled),
+            1 => Ok(NoiseGate::Percent2),
+            2 => Ok(NoiseGate::Percent4),
+            3 => Ok(NoiseGate::Percent8),
+            _ => Err(val),
+        }
+    }
+}
+
+impl From<u8> for NoiseGate {
+    fn from(val: u8) -> NoiseGate {
+        NoiseGate::try_from_bits(val).unwrap_or_else(|_| unreachable!("impossible NoiseGate value"))
+    }
+}
+
+/// The kind of signal fed to the IN/TRIG pin in
+/// `Mode::PwmInputAndAnalogInput` and `Mode::AudioToVibe`
+#[derive(Debug, Clone, Copy)]
+pub enum InputSignal {
+    /// A PWM signal whose duty cycle sets the amplitude
+    Pwm,
+    /// A DC-coupled analog signal whose level sets the amplitude
+    Analog,
+    /// An AC-coupled analog signal, biased by the 0.9 V common-mode drive.
+    /// This is required for `Mode::AudioToVibe`.
+    AcCoupledAnalog,
+}
+
+/// The input level, in millivolts, that produces full-scale drive in
+/// analog input mode
+pub const ANALOG_INPUT_FULL_SCALE_MV: u16 = 1800;
+
+/// Computes the DAC code to feed IN/TRIG in analog input mode for a drive
+/// `duty` between 0 (no drive) and 255 (full scale).
+/// The analog input is referenced to 1.8 V and the device has no register
+/// to rescale it, so a DAC with a different output range (such as a 3.3 V
+/// DAC) must stay within 0-1.8 V; this maps the duty onto that portion of
+/// the DAC's range.  `dac_max_code` is the DAC's largest code (e.g. 4095
+/// for 12 bits) and `dac_full_scale_mv` the voltage it produces.
+pub fn analog_input_dac_code(duty: u8, dac_max_code: u16, dac_full_scale_mv: u16) -> u16 {
+    if dac_full_scale_mv == 0 {
+        return 0;
+    }
+    let target_mv = u32::from(duty) * u32::from(ANALOG_INPUT_FULL_SCALE_MV) / 255;
+    let code = target_mv * u32::from(dac_max_code) / u32::from(dac_full_scale_mv);
+    code.min(u32::from(dac_max_code)) as u16
+}
+
+/// Returns a best-effort estimate, in milliseconds, of how long a
+/// sequence takes to play, using `Effect::approx_duration_ms` for effects
+/// and 10 ms per interval for waits.  Unknown effect IDs count as zero.
+pub fn estimated_duration(seq: &[SequenceEntry]) -> u32 {
+    seq.iter()
+        .map(|entry| match *entry {
+            SequenceEntry::Effect(effect) => u32::from(effect.approx_duration_ms()),
+            SequenceEntry::Wait(tens_of_ms) => u32::from(tens_of_ms) * 10,
+            SequenceEntry::Unknown(_) => 0,
+        })
+        .sum()
+}
+
+/// Returns the international morse code for a letter or digit as a string
+/// of dots and dashes
+fn morse_code(c: char) -> Option<&'static str> {
+    let code = match c.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// How the RTP_INPUT\[7:0\] value is interpreted in `Mode::RealTimePlayback`
+#[derive(Debug, Clone, Copy)]
+pub enum RtpFormat {
+    /// Two's complement; 0x00 is mid-scale (the power-on default)
+    Signed,
+    /// 0x00 is no drive and 0xff is full-scale drive
+    Unsigned,
+}
+
 bitfield!{
+    #[derive(Clone, Copy, PartialEq)]
     pub struct Control3Reg(u8);
     impl Debug;
 
@@ -685,7 +1699,34 @@ bitfield!{
     pub otp_program, set_otp_program: 1;
 }
 
-#[derive(Copy, Clone)]
+bitfield!{
+    #[derive(Clone, Copy, PartialEq)]
+    pub struct Control5Reg(u8);
+    impl Debug;
+
+    /// This bit selects number of cycles required to attempt synchronization
+    /// before transitioning to open loop when the LRA_AUTO_OPEN_LOOP bit is
+    /// asserted.
+    /// 0: 3 attempts
+    /// 1: 4 attempts
+    /// 2: 5 attempts
+    /// 3: 6 attempts
+    pub auto_ol_cnt, set_auto_ol_cnt: 7, 6;
+
+    /// This bit selects the automatic transition to open-loop drive when a
+    /// back-EMF signal is not detected (LRA only).
+    /// 0: Never transitions to open loop
+    /// 1: Automatically transitions to open loop
+    pub lra_auto_open_loop, set_lra_auto_open_loop: 5;
+
+    /// This bit selects the memory playback interval.
+    /// 0: 5 ms
+    /// 1: 1 ms
+    pub playback_interval, set_playback_interval: 4;
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Register {
     Status = 0,
@@ -714,6 +1755,17 @@ pub enum Register {
     SustainTimeOffsetNegative = 0xf,
     BrakeTimeOffset = 0x10,
 
+    /// Audio-to-vibe peak detection time and low-pass filter selection
+    AudioToVibeControl = 0x11,
+    /// Audio-to-vibe minimum input level; inputs below this are not played
+    AudioToVibeMinInputLevel = 0x12,
+    /// Audio-to-vibe input level that maps to the maximum output drive
+    AudioToVibeMaxInputLevel = 0x13,
+    /// Audio-to-vibe output drive applied at the minimum input level
+    AudioToVibeMinOutputDrive = 0x14,
+    /// Audio-to-vibe output drive applied at the maximum input level
+    AudioToVibeMaxOutputDrive = 0x15,
+
     /// This bit sets the reference voltage for full-scale output during closed-loop
     /// operation. The auto-calibration routine uses this register as an input, so this
     /// register must be written with the rated voltage value of the motor before
@@ -758,89 +1810,1250 @@ pub enum Register {
     Control2 = 0x1c,
     Control3 = 0x1d,
     Control4 = 0x1e,
-}
+    Control5 = 0x1f,
 
-/// The hard-coded address of the driver.  All drivers share the same
-/// address so that it is possible to broadcast on the bus and have
-/// multiple units emit the same waveform
-pub const ADDRESS: u8 = 0x5a;
+    /// This bit sets the period to be used for driving an LRA when Open-Loop
+    /// mode is selected.
+    /// LRA open-loop period (us) = OL_LRA_PERIOD[6:0] × 98.46 µs
+    LRAOpenLoopPeriod = 0x20,
 
-pub struct Drv2605<I2C>
-where
-    I2C: WriteRead + Write,
-{
-    i2c: I2C,
+    /// This bit provides a real-time reading of the supply voltage at the VDD pin.
+    /// The device must be actively sending a waveform to take reading.
+    /// VDD (V) = VBAT[7:0] × 5.6 V / 255
+    VBatVoltageMonitor = 0x21,
+
+    /// This bit reports the measurement of the LRA resonance period.
+    /// LRA period (us) = LRA_PERIOD[7:0] × 98.46 µs
+    LRAResonancePeriod = 0x22,
 }
 
-impl<I2C, E> Drv2605<I2C>
-where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
-{
-    /// Construct a driver instance, but don't do any initialization
-    pub fn new(i2c: I2C) -> Self {
-        Self { i2c }
+impl Register {
+    /// Returns the register at `address`, if there is one
+    fn from_address(address: u8) -> Option<Register> {
+        let register = match address {
+            0x00 => Register::Status,
+            0x01 => Register::Mode,
+            0x02 => Register::RealTimePlaybackInput,
+            0x03 => Register::Register3,
+            0x04 => Register::WaveformSequence0,
+            0x05 => Register::WaveformSequence1,
+            0x06 => Register::WaveformSequence2,
+            0x07 => Register::WaveformSequence3,
+            0x08 => Register::WaveformSequence4,
+            0x09 => Register::WaveformSequence5,
+            0x0a => Register::WaveformSequence6,
+            0x0b => Register::WaveformSequence7,
+            0x0c => Register::Go,
+            0x0d => Register::OverdriveTimeOffset,
+            0x0e => Register::SustainTimeOffsetPositive,
+            0x0f => Register::SustainTimeOffsetNegative,
+            0x10 => Register::BrakeTimeOffset,
+            0x11 => Register::AudioToVibeControl,
+            0x12 => Register::AudioToVibeMinInputLevel,
+            0x13 => Register::AudioToVibeMaxInputLevel,
+            0x14 => Register::AudioToVibeMinOutputDrive,
+            0x15 => Register::AudioToVibeMaxOutputDrive,
+            0x16 => Register::RatedVoltage,
+            0x17 => Register::OverdriveClampVoltage,
+            0x18 => Register::AutoCalibrationCompensationResult,
+            0x19 => Register::AutoCalibrationBackEMFResult,
+            0x1a => Register::FeedbackControl,
+            0x1b => Register::Control1,
+            0x1c => Register::Control2,
+            0x1d => Register::Control3,
+            0x1e => Register::Control4,
+            0x1f => Register::Control5,
+            0x20 => Register::LRAOpenLoopPeriod,
+            0x21 => Register::VBatVoltageMonitor,
+            0x22 => Register::LRAResonancePeriod,
+            _ => return None,
+        };
+        Some(register)
     }
 
-    pub fn init_open_loop_erm(&mut self) -> Result<(), E> {
-        self.set_standby(false)?;
-        self.set_realtime_playback_input(0)?;
-        self.set_waveform(&[
-            WaveformReg::new_effect(Effect::StrongClick100),
-            WaveformReg::new_stop(),
-            WaveformReg::new_stop(),
-            WaveformReg::new_stop(),
-            WaveformReg::new_stop(),
-            WaveformReg::new_stop(),
-            WaveformReg::new_stop(),
-            WaveformReg::new_stop(),
-        ])?;
+    /// Returns the datasheet power-on reset value of the register, or
+    /// `None` for the status register and those that report measurement
+    /// or calibration results.
+    pub fn default_value(&self) -> Option<u8> {
+        let value = match *self {
+            Register::Status
+            | Register::AutoCalibrationCompensationResult
+            | Register::AutoCalibrationBackEMFResult
+            | Register::VBatVoltageMonitor
+            | Register::LRAResonancePeriod => return None,
+            Register::Mode => 0x40,
+            Register::Register3 => 0x01,
+            Register::WaveformSequence0 => 0x01,
+            Register::AudioToVibeControl => 0x05,
+            Register::AudioToVibeMinInputLevel => 0x19,
+            Register::AudioToVibeMaxInputLevel => 0xff,
+            Register::AudioToVibeMinOutputDrive => 0x19,
+            Register::AudioToVibeMaxOutputDrive => 0xff,
+            Register::RatedVoltage => 0x3e,
+            Register::OverdriveClampVoltage => 0x8c,
+            Register::FeedbackControl => 0x36,
+            Register::Control1 => 0x93,
+            Register::Control2 => 0xf5,
+            Register::Control3 => 0xa0,
+            Register::Control4 => 0x20,
+            Register::Control5 => 0x80,
+            Register::LRAOpenLoopPeriod => 0x33,
+            Register::RealTimePlaybackInput
+            | Register::WaveformSequence1
+            | Register::WaveformSequence2
+            | Register::WaveformSequence3
+            | Register::WaveformSequence4
+            | Register::WaveformSequence5
+            | Register::WaveformSequence6
+            | Register::WaveformSequence7
+            | Register::Go
+            | Register::OverdriveTimeOffset
+            | Register::SustainTimeOffsetPositive
+            | Register::SustainTimeOffsetNegative
+            | Register::BrakeTimeOffset => 0x00,
+        };
+        Some(value)
+    }
+}
 
-        let mut feedback = FeedbackControlReg(self.read(Register::FeedbackControl)?);
-        feedback.set_n_erm_lra(false);
-        self.write(Register::FeedbackControl, feedback.0)?;
+/// The inputs to the auto-calibration routine.  These need to be
+/// programmed before the routine is run; see the datasheet sections on
+/// Rated Voltage and Overdrive Voltage-Clamp Programming for how to compute
+/// `rated` and `clamp` for a given actuator.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct CalibrationParams {
+    /// Feedback gain ratio between braking gain and driving gain
+    pub brake_factor: BrakeFactor,
+    /// LOOP_GAIN[1:0]; 0: Low, 1: Medium, 2: High, 3: Very High
+    pub loop_gain: u8,
+    /// AUTO_CAL_TIME[1:0]; the length of the auto calibration time
+    pub auto_cal_time: u8,
+    /// RATED_VOLTAGE[7:0]
+    pub rated: u8,
+    /// OD_CLAMP[7:0]
+    pub clamp: u8,
+    /// DRIVE_TIME[4:0]
+    pub drive_time: u8,
+}
 
-        let mut control3 = Control3Reg(self.read(Register::Control3)?);
-        control3.set_erm_open_loop(true);
-        self.write(Register::Control3, control3.0)?;
-        Ok(())
-    }
+/// Identifies a `CalibrationParams` field whose value doesn't fit in its
+/// register field; the offending value is included
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamError {
+    /// `loop_gain` must be 0-3
+    LoopGain(u8),
+    /// `auto_cal_time` must be 0-3
+    AutoCalTime(u8),
+    /// `drive_time` must be 0-31
+    DriveTime(u8),
+}
 
-    /// Write `value` to `register`
-    fn write(&mut self, register: Register, value: u8) -> Result<(), E> {
-        self.i2c.write(ADDRESS, &[register as u8, value])
+/// Rounds `value` to the nearest integer, saturating at the bounds of `u8`
+/// (NaN becomes 0)
+fn saturate_u8(value: f32) -> u8 {
+    if value >= 255.0 {
+        255
+    } else if value > 0.0 {
+        (value + 0.5) as u8
+    } else {
+        0
     }
+}
 
-    /// Read an 8-bit value from the register
-    fn read(&mut self, register: Register) -> Result<u8, E> {
-        let mut buf = [0u8; 1];
-        self.i2c.write_read(ADDRESS, &[register as u8], &mut buf)?;
-        Ok(buf[0])
+/// Square root by Newton's method, since `core` doesn't provide one.
+/// Returns 0 for inputs that aren't positive.
+fn sqrt(value: f32) -> f32 {
+    if value.is_nan() || value <= 0.0 {
+        return 0.0;
     }
-
-    pub fn get_status(&mut self) -> Result<StatusReg, E> {
-        self.read(Register::Status).map(StatusReg)
+    let mut root = if value > 1.0 { value } else { 1.0 };
+    for _ in 0..20 {
+        root = 0.5 * (root + value / root);
     }
+    root
+}
 
-    pub fn get_mode(&mut self) -> Result<ModeReg, E> {
-        self.read(Register::Mode).map(ModeReg)
+/// The fraction of each ERM drive period spent driving, which scales the
+/// ERM voltage equations.  This assumes the default 75 µs IDISS_TIME and
+/// BLANKING_TIME.
+fn erm_drive_fraction(drive_time_ms: f32) -> f32 {
+    (drive_time_ms - 0.3) / (drive_time_ms + 0.075 + 0.075)
+}
+
+/// Computes RATED_VOLTAGE[7:0] for an LRA from its rated RMS voltage,
+/// using the closed-loop LRA equation from the datasheet's Rated Voltage
+/// Programming section.  `sample_time_us` is the auto-resonance
+/// SAMPLE_TIME (300 µs by default).  The result saturates at the
+/// register's range.
+///
+/// ```
+/// use drv2605::{lra_rated_voltage_reg, well_known};
+///
+/// assert_eq!(lra_rated_voltage_reg(2.0, 235.0, 300.0), well_known::LRA_235HZ.rated);
+/// ```
+pub fn lra_rated_voltage_reg(v_rms: f32, resonant_hz: f32, sample_time_us: f32) -> u8 {
+    let sample_time = sample_time_us * 1e-6;
+    saturate_u8(v_rms * sqrt(1.0 - (4.0 * sample_time + 300e-6) * resonant_hz) / 20.58e-3)
+}
+
+/// The inverse of `lra_rated_voltage_reg`: returns the rated RMS voltage
+/// that a RATED_VOLTAGE[7:0] value represents for an LRA
+pub fn lra_rated_voltage_volts(reg: u8, resonant_hz: f32, sample_time_us: f32) -> f32 {
+    let sample_time = sample_time_us * 1e-6;
+    let scale = sqrt(1.0 - (4.0 * sample_time + 300e-6) * resonant_hz);
+    if scale == 0.0 {
+        return 0.0;
     }
+    f32::from(reg) * 20.58e-3 / scale
+}
 
-    /// performs the equivalent operation of power
-    /// cycling the device. Any playback operations are immediately interrupted,
-    /// and all registers are reset to the default values.
-    pub fn reset(&mut self) -> Result<(), E> {
+/// Computes RATED_VOLTAGE[7:0] for an ERM from its rated average (DC)
+/// voltage, using the closed-loop ERM equation from the datasheet's Rated
+/// Voltage Programming section.  `drive_time_ms` is the ERM drive time
+/// selected by DRIVE_TIME (see `CalibrationParams::drive_time`).  The
+/// result saturates at the register's range.
+pub fn erm_rated_voltage_reg(v_avg: f32, drive_time_ms: f32) -> u8 {
+    saturate_u8(v_avg / (21.18e-3 * erm_drive_fraction(drive_time_ms)))
+}
+
+/// The inverse of `erm_rated_voltage_reg`: returns the average voltage
+/// that a RATED_VOLTAGE[7:0] value represents for an ERM
+pub fn erm_rated_voltage_volts(reg: u8, drive_time_ms: f32) -> f32 {
+    f32::from(reg) * 21.18e-3 * erm_drive_fraction(drive_time_ms)
+}
+
+/// Computes OD_CLAMP[7:0] for an LRA from the peak voltage that the
+/// overdrive and braking should be limited to, using the LRA equation from
+/// the datasheet's Overdrive Voltage-Clamp Programming section.  The result
+/// saturates at the register's range.
+///
+/// The clamp equations differ by motor type just as the rated voltage
+/// ones do, so be sure to use the function for the motor that is
+/// connected.  In open-loop operation OD_CLAMP also sets the full-scale
+/// drive voltage.
+///
+/// ```
+/// use drv2605::{lra_overdrive_clamp_reg, lra_overdrive_clamp_volts, well_known};
+///
+/// assert_eq!(lra_overdrive_clamp_reg(2.5, 235.0), well_known::LRA_235HZ.clamp);
+/// let volts = lra_overdrive_clamp_volts(well_known::LRA_175HZ.clamp, 175.0);
+/// assert!((volts - 2.5).abs() < 0.02);
+/// ```
+pub fn lra_overdrive_clamp_reg(v_peak: f32, resonant_hz: f32) -> u8 {
+    let scale = 21.32e-3 * sqrt(1.0 - resonant_hz * 800e-6);
+    if scale == 0.0 {
+        return 0xff;
+    }
+    saturate_u8(v_peak / scale)
+}
+
+/// The inverse of `lra_overdrive_clamp_reg`: returns the peak voltage that
+/// an OD_CLAMP[7:0] value represents for an LRA
+pub fn lra_overdrive_clamp_volts(reg: u8, resonant_hz: f32) -> f32 {
+    f32::from(reg) * 21.32e-3 * sqrt(1.0 - resonant_hz * 800e-6)
+}
+
+/// Computes OD_CLAMP[7:0] for an ERM from the average voltage that the
+/// overdrive and braking should be limited to, using the ERM equation from
+/// the datasheet's Overdrive Voltage-Clamp Programming section.
+/// `drive_time_ms` is the ERM drive time selected by DRIVE_TIME.  See
+/// `lra_overdrive_clamp_reg` for the LRA equivalent.
+pub fn erm_overdrive_clamp_reg(v_avg: f32, drive_time_ms: f32) -> u8 {
+    saturate_u8(v_avg / (21.64e-3 * erm_drive_fraction(drive_time_ms)))
+}
+
+/// The inverse of `erm_overdrive_clamp_reg`: returns the average voltage
+/// that an OD_CLAMP[7:0] value represents for an ERM
+pub fn erm_overdrive_clamp_volts(reg: u8, drive_time_ms: f32) -> f32 {
+    f32::from(reg) * 21.64e-3 * erm_drive_fraction(drive_time_ms)
+}
+
+/// Computes DRIVE_TIME[4:0] for the desired drive time in microseconds,
+/// rounding to the nearest step and saturating at the field's range.
+/// For an LRA the drive time is `DRIVE_TIME × 0.1 ms + 0.5 ms` and should
+/// be about half the resonant period; for an ERM it is
+/// `DRIVE_TIME × 0.2 ms + 1 ms`, the sampling rate of the back-EMF.
+///
+/// ```
+/// use drv2605::{drive_time_from_micros, drive_time_micros};
+///
+/// // Half the 4.26 ms period of a 235 Hz LRA
+/// assert_eq!(drive_time_from_micros(2130, true), 0x10);
+/// assert_eq!(drive_time_micros(0x13, false), 4800);
+/// ```
+pub fn drive_time_from_micros(us: u16, lra: bool) -> u8 {
+    let (offset, step) = if lra { (500, 100) } else { (1000, 200) };
+    let steps = (u32::from(us.saturating_sub(offset)) + step / 2) / step;
+    steps.min(0x1f) as u8
+}
+
+/// The inverse of `drive_time_from_micros`: returns the drive time in
+/// microseconds that DRIVE_TIME[4:0] selects
+pub fn drive_time_micros(drive_time: u8, lra: bool) -> u16 {
+    let drive_time = u16::from(drive_time & 0x1f);
+    if lra {
+        drive_time * 100 + 500
+    } else {
+        drive_time * 200 + 1000
+    }
+}
+
+impl Default for CalibrationParams {
+    /// The power-on values from the datasheet
+    fn default() -> Self {
+        Self {
+            brake_factor: BrakeFactor::X4,
+            loop_gain: 1,
+            auto_cal_time: 2,
+            rated: 0x3e,
+            clamp: 0x8c,
+            drive_time: 0x13,
+        }
+    }
+}
+
+impl CalibrationParams {
+    /// Computes the calibration inputs for an LRA from its datasheet
+    /// rating: the resonant frequency, the rated (RMS) voltage and the
+    /// maximum voltage allowed during overdrive, both in millivolts.
+    /// DRIVE_TIME is set to half the resonant period, and the rated
+    /// voltage and overdrive clamp come from the closed-loop LRA equations
+    /// evaluated at that same frequency (with the default 300 µs
+    /// SAMPLE_TIME), so the three values are consistent with each other.
+    /// Each result saturates at its register's range.  The result must be
+    /// programmed with `lra: true`.
+    pub fn for_lra(resonant_hz: f32, rated_mv: u16, max_mv: u16) -> CalibrationParams {
+        let half_period_ms = 500.0 / resonant_hz;
+        let drive_time = saturate_u8((half_period_ms - 0.5) / 0.1).min(0x1f);
+        let rated = lra_rated_voltage_reg(f32::from(rated_mv) / 1000.0, resonant_hz, 300.0);
+        let clamp = lra_overdrive_clamp_reg(f32::from(max_mv) / 1000.0, resonant_hz);
+
+        CalibrationParams {
+            rated,
+            clamp,
+            drive_time,
+            ..CalibrationParams::default()
+        }
+    }
+
+    /// Checks that each field fits in the bits its register provides,
+    /// returning the first one that doesn't.  `to_registers` silently
+    /// truncates out of range values.
+    pub fn validate(&self) -> Result<(), ParamError> {
+        if self.loop_gain > 3 {
+            return Err(ParamError::LoopGain(self.loop_gain));
+        }
+        if self.auto_cal_time > 3 {
+            return Err(ParamError::AutoCalTime(self.auto_cal_time));
+        }
+        if self.drive_time > 0x1f {
+            return Err(ParamError::DriveTime(self.drive_time));
+        }
+        Ok(())
+    }
+
+    /// Returns the register/value pairs that these parameters produce on
+    /// a freshly reset device, for example to build a `DeviceProfile`
+    /// with `DeviceProfile::from_registers`.
+    /// `lra` selects the motor type in the feedback control register.
+    /// Each value is the complete register contents: the fields that are
+    /// not part of the calibration parameters hold their power-on defaults.
+    pub fn to_registers(&self, lra: bool) -> [(Register, u8); 5] {
+        let mut feedback = FeedbackControlReg(0);
+        feedback.set_n_erm_lra(lra);
+        feedback.set_fb_brake_factor(self.brake_factor as u8);
+        feedback.set_loop_gain(self.loop_gain);
+        feedback.set_bemf_gain(2);
+
+        let mut control1 = Control1Reg(0);
+        control1.set_startup_boost(true);
+        control1.set_drive_time(self.drive_time);
+
+        let mut control4 = Control4Reg(0);
+        control4.set_auto_cal_time(self.auto_cal_time);
+
+        [
+            (Register::FeedbackControl, feedback.0),
+            (Register::RatedVoltage, self.rated),
+            (Register::OverdriveClampVoltage, self.clamp),
+            (Register::Control1, control1.0),
+            (Register::Control4, control4.0),
+        ]
+    }
+}
+
+/// The library waveform time offsets in signed milliseconds, scaled by
+/// the playback interval in effect when they were read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeOffsetsMs {
+    pub overdrive: i16,
+    pub sustain_positive: i16,
+    pub sustain_negative: i16,
+    pub brake: i16,
+}
+
+/// The registers that determine the "feel" of playback: the library
+/// waveform time offsets and the feedback and control settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TuningSnapshot {
+    pub overdrive_time_offset: i8,
+    pub sustain_time_offset_positive: i8,
+    pub sustain_time_offset_negative: i8,
+    pub brake_time_offset: i8,
+    pub feedback: FeedbackControlReg,
+    pub control1: Control1Reg,
+    pub control2: Control2Reg,
+    pub control3: Control3Reg,
+}
+
+/// The results of the auto-calibration routine.  These can be saved after
+/// calibrating once and loaded into other units (or after a power cycle)
+/// with `Drv2605::set_load_params` instead of calibrating again.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct LoadParams {
+    /// A_CAL_COMP[7:0]; the auto-calibration compensation result
+    pub comp: u8,
+    /// A_CAL_BEMF[7:0]; the auto-calibration back-EMF result
+    pub bemf: u8,
+    /// BEMF_GAIN[1:0]; the back-EMF amplifier gain
+    pub gain: u8,
+}
+
+/// The temperature, in °C, that `LoadParams::at_temperature` assumes the
+/// baseline calibration was performed at
+pub const CALIBRATION_REFERENCE_TEMP_C: f32 = 25.0;
+
+/// Returns the back-EMF amplifier gain that a BEMF_GAIN[1:0] value
+/// selects; the same field means different multipliers for ERMs and LRAs
+pub fn bemf_gain_multiplier(gain: u8, lra: bool) -> f32 {
+    const ERM: [f32; 4] = [0.33, 1.0, 1.8, 4.0];
+    const LRA: [f32; 4] = [5.0, 10.0, 20.0, 30.0];
+    let table = if lra { &LRA } else { &ERM };
+    table[usize::from(gain & 3)]
+}
+
+impl LoadParams {
+    /// Returns the back-EMF amplifier gain multiplier for `gain`; see
+    /// `bemf_gain_multiplier`
+    pub fn gain_multiplier(&self, lra: bool) -> f32 {
+        bemf_gain_multiplier(self.gain, lra)
+    }
+
+    /// Returns these results adjusted for operation at `temp_c`, assuming
+    /// they were obtained at `CALIBRATION_REFERENCE_TEMP_C` and that the
+    /// compensation and back-EMF results both change linearly by the
+    /// fraction `coeff` per °C (e.g. `-0.002` for -0.2%/°C).  The gain is
+    /// unchanged, and the adjusted values saturate at the register range.
+    pub fn at_temperature(&self, temp_c: f32, coeff: f32) -> LoadParams {
+        let factor = 1.0 + coeff * (temp_c - CALIBRATION_REFERENCE_TEMP_C);
+        LoadParams {
+            comp: saturate_u8(f32::from(self.comp) * factor),
+            bemf: saturate_u8(f32::from(self.bemf) * factor),
+            gain: self.gain,
+        }
+    }
+}
+
+/// The decoded contents of every register, as returned by
+/// `Drv2605::read_state`.  Printing this with `{:#?}` gives a complete
+/// picture of the device configuration, which is handy for bug reports.
+#[derive(Debug)]
+pub struct DeviceState {
+    pub status: StatusReg,
+    pub mode: ModeReg,
+    pub rtp_input: u8,
+    pub register3: RegisterThree,
+    pub waveform: [WaveformReg; 8],
+    pub go: GoReg,
+    pub overdrive_time_offset: i8,
+    pub sustain_time_offset_positive: i8,
+    pub sustain_time_offset_negative: i8,
+    pub brake_time_offset: i8,
+    pub audio_to_vibe_control: u8,
+    pub audio_to_vibe_min_input_level: u8,
+    pub audio_to_vibe_max_input_level: u8,
+    pub audio_to_vibe_min_output_drive: u8,
+    pub audio_to_vibe_max_output_drive: u8,
+    pub rated_voltage: u8,
+    pub overdrive_clamp_voltage: u8,
+    pub auto_calibration_compensation_result: u8,
+    pub auto_calibration_back_emf_result: u8,
+    pub feedback: FeedbackControlReg,
+    pub control1: Control1Reg,
+    pub control2: Control2Reg,
+    pub control3: Control3Reg,
+    pub control4: Control4Reg,
+    pub control5: Control5Reg,
+    pub lra_open_loop_period: u8,
+    pub vbat: u8,
+    pub lra_resonance_period: u8,
+}
+
+/// Tracks when a fired sequence is expected to finish, so that an
+/// application can schedule its next action on its own timer instead of
+/// polling the GO bit.  Created by `Drv2605::go_with_completion`, or
+/// directly from a start time and an expected duration.  All times are
+/// in milliseconds on the caller's clock, which may wrap around.
+#[derive(Debug, Clone, Copy)]
+pub struct CompletionWaiter {
+    started_ms: u32,
+    duration_ms: u32,
+}
+
+impl CompletionWaiter {
+    pub fn new(started_ms: u32, duration_ms: u32) -> Self {
+        Self {
+            started_ms,
+            duration_ms,
+        }
+    }
+
+    /// The time at which playback is expected to be complete
+    pub fn deadline_ms(&self) -> u32 {
+        self.started_ms.wrapping_add(self.duration_ms)
+    }
+
+    /// How long until playback is expected to be complete; 0 once the
+    /// deadline has passed
+    pub fn remaining_ms(&self, now_ms: u32) -> u32 {
+        let elapsed = now_ms.wrapping_sub(self.started_ms);
+        self.duration_ms.saturating_sub(elapsed)
+    }
+
+    /// Returns true once the deadline has passed
+    pub fn is_done(&self, now_ms: u32) -> bool {
+        self.remaining_ms(now_ms) == 0
+    }
+
+    /// Returns true if the deadline has passed and the device agrees, by
+    /// having cleared the GO bit.  The device is only read once the
+    /// deadline has passed, so this can be called from a periodic timer
+    /// without costing bus traffic while the sequence is still playing.
+    pub fn confirm<I2C, E>(&self, drv: &mut Drv2605<I2C>, now_ms: u32) -> Result<bool, DrvError<E>>
+    where
+        I2C: WriteRead<Error = E> + Write<Error = E>,
+    {
+        if !self.is_done(now_ms) {
+            return Ok(false);
+        }
+        Ok(!drv.get_go()?.go())
+    }
+}
+
+/// A real-time playback value captured by an `RtpRecorder`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RtpSample {
+    /// When the value was written, in milliseconds from any fixed origin
+    pub at_ms: u32,
+    /// The unsigned RTP duty that was written
+    pub duty: u8,
+}
+
+/// Records unsigned RTP writes into a caller-provided buffer while passing
+/// them through to the device.  Created by `Drv2605::record`.
+pub struct RtpRecorder<'a, 'b, I2C>
+where
+    I2C: 'a + WriteRead + Write,
+{
+    driver: &'a mut Drv2605<I2C>,
+    buffer: &'b mut [RtpSample],
+    len: usize,
+}
+
+impl<'a, 'b, I2C, E> RtpRecorder<'a, 'b, I2C>
+where
+    I2C: WriteRead<Error = E> + Write<Error = E>,
+{
+    /// Writes `duty` with `Drv2605::set_rtp` and records it as having
+    /// happened at `at_ms`.  Returns `DrvError::BufferFull` without writing
+    /// anything once the buffer is full.
+    pub fn set_rtp(&mut self, duty: u8, at_ms: u32) -> Result<(), DrvError<E>> {
+        if self.len >= self.buffer.len() {
+            return Err(DrvError::BufferFull);
+        }
+        self.driver.set_rtp(duty)?;
+        self.buffer[self.len] = RtpSample { at_ms, duty };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// The samples recorded so far
+    pub fn samples(&self) -> &[RtpSample] {
+        &self.buffer[..self.len]
+    }
+}
+
+/// Whether the drive is regulated using feedback from the actuator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopMode {
+    OpenLoop,
+    /// Required for automatic overdrive and braking
+    ClosedLoop,
+}
+
+/// Whether a traced register value was read from or written to the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Read,
+    Write,
+}
+
+/// The number of registers, from `Register::Status` to
+/// `Register::LRAResonancePeriod`
+const REGISTER_COUNT: usize = Register::LRAResonancePeriod as usize + 1;
+
+/// The register values a correctly configured device is expected to hold,
+/// for checking a real device with `Drv2605::verify_configuration`.
+/// Registers without an expected value are not compared.
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceProfile {
+    values: [Option<u8>; REGISTER_COUNT],
+}
+
+impl Default for DeviceProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeviceProfile {
+    /// Returns a profile that doesn't expect anything
+    pub fn new() -> Self {
+        Self {
+            values: [None; REGISTER_COUNT],
+        }
+    }
+
+    /// Returns a profile expecting each `(register, value)` pair, such as
+    /// those produced by `CalibrationParams::to_registers`
+    pub fn from_registers(expected: &[(Register, u8)]) -> Self {
+        expected
+            .iter()
+            .fold(Self::new(), |profile, &(register, value)| {
+                profile.with_register(register, value)
+            })
+    }
+
+    /// Adds the expectation that `register` holds `value`
+    pub fn with_register(mut self, register: Register, value: u8) -> Self {
+        self.values[register as usize] = Some(value);
+        self
+    }
+
+    /// Returns the value expected for `register`, if any
+    pub fn expected(&self, register: Register) -> Option<u8> {
+        self.values[register as usize]
+    }
+}
+
+/// A register that read back a different value than its `DeviceProfile`
+/// expected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterMismatch {
+    pub register: Register,
+    pub expected: u8,
+    pub actual: u8,
+}
+
+/// Every register that differs from a `DeviceProfile`, as returned by
+/// `Drv2605::verify_configuration`
+#[derive(Debug, Clone, Copy)]
+pub struct ProfileDiff {
+    mismatches: [Option<RegisterMismatch>; REGISTER_COUNT],
+}
+
+impl ProfileDiff {
+    /// Returns true if the device matched the profile
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Returns the number of registers that differ
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Iterates over the differing registers in address order
+    pub fn iter(&self) -> impl Iterator<Item = &RegisterMismatch> {
+        self.mismatches.iter().flatten()
+    }
+}
+
+/// Errors reported by the driver
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DrvError<E> {
+    /// The underlying I2C transaction failed
+    I2c(E),
+    /// The device did not finish an operation in the allotted time
+    Timeout,
+    /// The RTP value doesn't match the configured `RtpFormat`
+    FormatMismatch,
+    /// The OTP memory has already been programmed and cannot be written again
+    OtpAlreadyProgrammed,
+    /// More effects were supplied than fit in the eight sequencer slots
+    SequenceTooLong,
+    /// The device latched an over-current or over-temperature fault
+    Fault(StatusReg),
+    /// The requested combination of settings can't produce any output
+    InvalidConfig,
+    /// The auto-calibration routine did not converge
+    CalibrationFailed,
+    /// The diagnostic routine found the actuator missing or shorted; the
+    /// status register read at the end of the routine is included
+    DeviceDiagnosticFailed(StatusReg),
+    /// There is no room left in the caller-provided buffer
+    BufferFull,
+    /// The previous trigger is still expected to be playing; see
+    /// `Drv2605::with_min_retrigger_ms`
+    TooSoon { remaining_ms: u16 },
+    /// A `CalibrationParams` field is out of range for its register
+    InvalidParams(ParamError),
+    /// The DEVICE_ID isn't one of the known `DeviceVariant`s
+    UnknownDevice(u8),
+    /// MODE[2:0] doesn't decode to a `Mode`
+    UnknownMode(u8),
+}
+
+impl<E> DrvError<E> {
+    /// Returns the underlying I2C error, if this is a bus failure.  This
+    /// makes it easy to inspect NACKs or arbitration loss on a shared bus
+    /// without matching on every variant.
+    pub fn i2c_error(&self) -> Option<&E> {
+        match *self {
+            DrvError::I2c(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// How long to wait for a programmed waveform sequence to finish playing.
+/// Eight back-to-back alerts are the longest effects-only sequence.
+const PLAYBACK_TIMEOUT_MS: u16 = 10_000;
+
+/// The default budget for device operations that complete by clearing a
+/// bit, such as OTP programming.  See `Drv2605::with_poll_timeout_ms`.
+pub const DEFAULT_POLL_TIMEOUT_MS: u16 = 2_000;
+
+/// Returns true for the modes that take their input from the IN/TRIG pin,
+/// and so depend on the AC_COUPLE and N_PWM_ANALOG bits
+fn mode_uses_input(mode: Mode) -> bool {
+    matches!(
+        mode,
+        Mode::ExternalTriggerRisingEdge
+            | Mode::ExternalTriggerLevelMode
+            | Mode::PwmInputAndAnalogInput
+            | Mode::AudioToVibe
+    )
+}
+
+/// Returns true if the AC_COUPLE and N_PWM_ANALOG bits can produce output
+/// in `mode`.  The common-mode drive is only meaningful for an AC-coupled
+/// analog input, and audio-to-vibe requires exactly that.
+fn input_config_valid(mode: Mode, control1: Control1Reg, control3: Control3Reg) -> bool {
+    let ac_couple = control1.ac_couple();
+    let analog = control3.n_pwm_analog();
+    match mode {
+        Mode::PwmInputAndAnalogInput => analog || !ac_couple,
+        Mode::AudioToVibe => analog && ac_couple,
+        _ => !ac_couple,
+    }
+}
+
+/// The hard-coded address of the driver.  All drivers share the same
+/// address so that it is possible to broadcast on the bus and have
+/// multiple units emit the same waveform
+pub const ADDRESS: u8 = 0x5a;
+
+pub struct Drv2605<I2C>
+where
+    I2C: WriteRead + Write,
+{
+    i2c: I2C,
+    poll_timeout_ms: u16,
+    last_calibration: Option<bool>,
+    min_retrigger_ms: u16,
+    last_trigger_ms: Option<u32>,
+    mode_cache: Option<u8>,
+    pending_mode: Option<u8>,
+    trace: Option<fn(Register, u8, Direction)>,
+    max_continuous_drive_ms: u16,
+    last_keepalive_ms: Option<u32>,
+    idle_standby_ms: u16,
+    idle_since_ms: Option<u32>,
+    warmup: bool,
+    analog_input_range_mv: (u16, u16),
+}
+
+impl<I2C, E> Drv2605<I2C>
+where
+    I2C: WriteRead<Error = E> + Write<Error = E>,
+{
+    /// Construct a driver instance, but don't do any initialization
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            poll_timeout_ms: DEFAULT_POLL_TIMEOUT_MS,
+            last_calibration: None,
+            min_retrigger_ms: 0,
+            last_trigger_ms: None,
+            mode_cache: None,
+            pending_mode: None,
+            trace: None,
+            max_continuous_drive_ms: 0,
+            last_keepalive_ms: None,
+            idle_standby_ms: 0,
+            idle_since_ms: None,
+            warmup: false,
+            analog_input_range_mv: (0, ANALOG_INPUT_FULL_SCALE_MV),
+        }
+    }
+
+    /// Consumes the driver and returns the I2C bus, so that it can be
+    /// handed to another driver.  The device is left in whatever state it
+    /// was in.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Constructs a driver for an ERM motor and gets it ready to play ROM
+    /// effects: the default `CalibrationParams` are programmed, auto
+    /// calibration is run, library A is selected for open-loop playback
+    /// (see `configure_rom`) and the device is taken out of standby.
+    pub fn new_erm_default<D: DelayMs<u16>>(i2c: I2C, delay: &mut D) -> Result<Self, DrvError<E>> {
+        let mut drv = Self::new(i2c);
+        drv.setup_default(MotorType::Erm, LibrarySelection::A, delay)?;
+        Ok(drv)
+    }
+
+    /// Like `new_erm_default`, but for an LRA: the device is calibrated
+    /// for closed-loop LRA operation and the LRA library is selected.
+    pub fn new_lra_default<D: DelayMs<u16>>(i2c: I2C, delay: &mut D) -> Result<Self, DrvError<E>> {
+        let mut drv = Self::new(i2c);
+        drv.setup_default(MotorType::Lra, LibrarySelection::LRA, delay)?;
+        Ok(drv)
+    }
+
+    fn setup_default<D: DelayMs<u16>>(
+        &mut self,
+        motor: MotorType,
+        library: LibrarySelection,
+        delay: &mut D,
+    ) -> Result<(), DrvError<E>> {
+        self.set_calibration_params(&CalibrationParams::default(), motor == MotorType::Lra)?;
+        self.calibrate(delay)?;
+        self.configure_rom(library, motor)?;
+        self.set_standby(false)
+    }
+
+    /// Sets the budget for operations that poll the device until it
+    /// reports completion, such as `calibrate`, `program_otp` and
+    /// `reset`.  The budget is counted in 1 ms delay steps between polls
+    /// and does not include the time spent on the bus, so the real elapsed
+    /// time is always somewhat longer.  A heavily loaded or slow (e.g. 100 kHz) bus
+    /// stretches every poll; if that causes spurious `DrvError::Timeout`
+    /// results, raise this value.
+    /// Defaults to `DEFAULT_POLL_TIMEOUT_MS`.
+    pub fn with_poll_timeout_ms(mut self, timeout_ms: u16) -> Self {
+        self.poll_timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Sets the minimum time that `trigger_at` enforces between
+    /// consecutive triggers.  Setting GO while a sequence is still playing
+    /// restarts it, so this should be at least the duration of the longest
+    /// sequence that is fired this way.  Defaults to 0, which disables the
+    /// check.
+    /// The driver has no clock of its own, so only `trigger_at`, which is
+    /// given the current time, honours this; `set_go`, `replay` and the
+    /// other methods that fire playback do not check it.
+    pub fn with_min_retrigger_ms(mut self, min_retrigger_ms: u16) -> Self {
+        self.min_retrigger_ms = min_retrigger_ms;
+        self
+    }
+
+    /// Returns the minimum time between triggers set by
+    /// `with_min_retrigger_ms`
+    pub fn min_retrigger_ms(&self) -> u16 {
+        self.min_retrigger_ms
+    }
+
+    /// Enables a watchdog for continuous drive: once armed by `keepalive`,
+    /// `tick` stops the motor if no further keepalive has arrived within
+    /// `max_continuous_drive_ms`.  Defaults to 0, which disables the
+    /// watchdog.
+    pub fn with_max_continuous_drive_ms(mut self, max_continuous_drive_ms: u16) -> Self {
+        self.max_continuous_drive_ms = max_continuous_drive_ms;
+        self
+    }
+
+    /// When enabled, every successful `calibrate` is followed by a brief,
+    /// low intensity hum that settles the actuator before the device is
+    /// returned to its previous mode (and so before it re-enters standby).
+    /// Some motors respond weakly to the very first drive after
+    /// calibration; the warm-up makes the first real effect feel the same
+    /// as the ones that follow it.  The hum is played from the first
+    /// waveform sequencer slot; the first two slots are restored
+    /// afterwards.  Defaults to false.
+    pub fn with_warmup(mut self, warmup: bool) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Installs a callback that is told about every register value the
+    /// driver reads or writes, in the order of the bus transactions.
+    /// Burst transfers are reported one register at a time.  This is meant
+    /// for debugging, for example to print the traffic when a setting
+    /// doesn't seem to take effect.
+    pub fn with_trace(mut self, trace: fn(Register, u8, Direction)) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
+    pub fn init_open_loop_erm(&mut self) -> Result<(), DrvError<E>> {
+        self.set_standby(false)?;
+        self.set_realtime_playback_input(0)?;
+        self.set_waveform(&[
+            WaveformReg::new_effect(Effect::StrongClick100),
+            WaveformReg::new_stop(),
+            WaveformReg::new_stop(),
+            WaveformReg::new_stop(),
+            WaveformReg::new_stop(),
+            WaveformReg::new_stop(),
+            WaveformReg::new_stop(),
+            WaveformReg::new_stop(),
+        ])?;
+
+        let mut feedback = FeedbackControlReg(self.read(Register::FeedbackControl)?);
+        feedback.set_n_erm_lra(false);
+        self.write(Register::FeedbackControl, feedback.0)?;
+
+        let mut control3 = Control3Reg(self.read(Register::Control3)?);
+        control3.set_erm_open_loop(true);
+        self.write(Register::Control3, control3.0)?;
+        Ok(())
+    }
+
+    /// Like `init_open_loop_erm`, but rides through a bus glitch right after
+    /// power-up: if the first attempt fails with an I2C error, it waits
+    /// 10 ms and tries once more before giving up, returning the error from
+    /// the second attempt.
+    pub fn init_open_loop_erm_with_retry<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), DrvError<E>> {
+        match self.init_open_loop_erm() {
+            Err(DrvError::I2c(_)) => {
+                delay.delay_ms(10);
+                self.init_open_loop_erm()
+            }
+            result => result,
+        }
+    }
+
+    /// Write `value` to `register`
+    fn write(&mut self, register: Register, value: u8) -> Result<(), DrvError<E>> {
+        self.i2c
+            .write(ADDRESS, &[register as u8, value])
+            .map_err(DrvError::I2c)?;
+        self.trace(register as u8, &[value], Direction::Write);
+        if register == Register::Mode {
+            // A DEV_RESET write puts the register back to its power-on value
+            let mode = ModeReg(value);
+            self.mode_cache = Some(if mode.dev_reset() {
+                ModeReg::default().0
+            } else {
+                mode.0
+            });
+        }
+        Ok(())
+    }
+
+    /// Read an 8-bit value from the register
+    fn read(&mut self, register: Register) -> Result<u8, DrvError<E>> {
+        let mut buf = [0u8; 1];
+        self.i2c
+            .write_read(ADDRESS, &[register as u8], &mut buf)
+            .map_err(DrvError::I2c)?;
+        self.trace(register as u8, &buf, Direction::Read);
+        Ok(buf[0])
+    }
+
+    /// Read consecutive registers starting at `start` into `buf`
+    fn read_registers(&mut self, start: Register, buf: &mut [u8]) -> Result<(), DrvError<E>> {
+        self.i2c
+            .write_read(ADDRESS, &[start as u8], buf)
+            .map_err(DrvError::I2c)?;
+        self.trace(start as u8, buf, Direction::Read);
+        Ok(())
+    }
+
+    /// Write `buf[1..]` to consecutive registers starting at the address
+    /// in `buf[0]`
+    fn write_registers(&mut self, buf: &[u8]) -> Result<(), DrvError<E>> {
+        self.i2c.write(ADDRESS, buf).map_err(DrvError::I2c)?;
+        self.trace(buf[0], &buf[1..], Direction::Write);
+        Ok(())
+    }
+
+    /// Reports `values`, transferred starting at register `start`, to the
+    /// trace callback if one is installed
+    fn trace(&self, start: u8, values: &[u8], direction: Direction) {
+        if let Some(trace) = self.trace {
+            for (address, &value) in (start..).zip(values.iter()) {
+                if let Some(register) = Register::from_address(address) {
+                    trace(register, value, direction);
+                }
+            }
+        }
+    }
+
+    pub fn get_status(&mut self) -> Result<StatusReg, DrvError<E>> {
+        self.read(Register::Status).map(StatusReg)
+    }
+
+    /// Reads the part number from the status register, returning
+    /// `DrvError::UnknownDevice` with the raw DEVICE_ID if it isn't one of
+    /// the known parts
+    pub fn variant(&mut self) -> Result<DeviceVariant, DrvError<E>> {
+        let id = self.get_status()?.device_id();
+        DeviceVariant::try_from(id).map_err(DrvError::UnknownDevice)
+    }
+
+    /// Reads the DEVICE_ID and returns the full identification of the part,
+    /// including parts this driver doesn't recognize.  Unlike `variant`,
+    /// this only fails if the bus does.
+    pub fn identify(&mut self) -> Result<ChipInfo, DrvError<E>> {
+        Ok(ChipInfo::from(self.get_status()?.device_id()))
+    }
+
+    /// Samples the latching over-temperature flag `samples` times, one
+    /// millisecond apart, and returns true only if it was set on every read.
+    /// The flag clears upon read, so a single read can report a transient
+    /// condition that has already passed; a persistent over-temperature
+    /// condition sets the flag again before the next sample.
+    pub fn is_overheating<D: DelayMs<u16>>(
+        &mut self,
+        samples: u8,
+        delay: &mut D,
+    ) -> Result<bool, DrvError<E>> {
+        if samples == 0 {
+            return Ok(false);
+        }
+        for i in 0..samples {
+            if i > 0 {
+                delay.delay_ms(1);
+            }
+            if !self.get_status()?.over_temp() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn get_mode(&mut self) -> Result<ModeReg, DrvError<E>> {
+        self.read(Register::Mode).map(ModeReg)
+    }
+
+    /// Returns the configured drive time in microseconds.
+    /// The DRIVE_TIME[4:0] field is interpreted according to the motor
+    /// type currently selected by the N_ERM_LRA bit; see
+    /// `drive_time_micros`.
+    pub fn drive_time_us(&mut self) -> Result<f32, DrvError<E>> {
+        let feedback = FeedbackControlReg(self.read(Register::FeedbackControl)?);
+        let control1 = Control1Reg(self.read(Register::Control1)?);
+        let us = drive_time_micros(control1.drive_time(), feedback.n_erm_lra());
+        Ok(f32::from(us))
+    }
+
+    /// performs the equivalent operation of power
+    /// cycling the device. Any playback operations are immediately interrupted,
+    /// and all registers are reset to the default values.
+    /// Waits for the device to clear the DEV_RESET bit, after which it is
+    /// back in standby with its power-on register values; returns
+    /// `DrvError::Timeout` if that doesn't happen within the poll timeout
+    /// (see `with_poll_timeout_ms`).
+    pub fn reset<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<(), DrvError<E>> {
         let mut mode = ModeReg(0);
         mode.set_dev_reset(true);
-        self.write(Register::Mode, mode.0)
+        self.write(Register::Mode, mode.0)?;
+
+        let mut elapsed = 0;
+        while ModeReg(self.read(Register::Mode)?).dev_reset() {
+            if elapsed >= self.poll_timeout_ms {
+                return Err(DrvError::Timeout);
+            }
+            delay.delay_ms(1);
+            elapsed += 1;
+        }
+        Ok(())
+    }
+
+    /// Checks whether the device appears to have been reset (by a power
+    /// loss or another bus master) since this driver last wrote the mode
+    /// register, by comparing the register against the value that was
+    /// written.  Returns true if they differ, in which case the device
+    /// should be reconfigured; the new value is remembered so that the
+    /// same reset is only reported once.
+    ///
+    /// A reset can only be noticed once the mode register has been moved
+    /// away from its power-on value, for example by leaving standby.
+    /// Returns false if this driver has not written the mode register yet.
+    pub fn detect_reset(&mut self) -> Result<bool, DrvError<E>> {
+        let expected = match self.mode_cache {
+            Some(expected) => expected,
+            None => return Ok(false),
+        };
+        let mut actual = ModeReg(self.read(Register::Mode)?);
+        if actual.dev_reset() {
+            actual = ModeReg::default();
+        }
+        self.mode_cache = Some(actual.0);
+        Ok(actual.0 != expected)
+    }
+
+    /// Returns the mode register to its power-on value: standby, with
+    /// `Mode::InternalTrigger` selected.  Unlike `reset`, the calibration
+    /// and control registers are left untouched, making this a quick way to
+    /// get back to a known idle state.
+    pub fn reset_mode(&mut self) -> Result<(), DrvError<E>> {
+        self.write(Register::Mode, ModeReg::default().0)
     }
 
     /// Put the device into standby mode, or wake it up from standby
-    pub fn set_standby(&mut self, standby: bool) -> Result<(), E> {
+    pub fn set_standby(&mut self, standby: bool) -> Result<(), DrvError<E>> {
         let mut mode = ModeReg(self.read(Register::Mode)?);
         mode.set_standby(standby);
         self.write(Register::Mode, mode.0)
     }
 
+    /// Select the operating `Mode`.  The standby bit is left unchanged.
+    pub fn set_mode(&mut self, mode: Mode) -> Result<(), DrvError<E>> {
+        self.check_input_config(mode)?;
+        let mut register = ModeReg(self.read(Register::Mode)?);
+        register.set_mode(mode as u8);
+        self.write(Register::Mode, register.0)
+    }
+
+    /// Selects how the IN/TRIG pin is interpreted by the PWM/analog and
+    /// audio-to-vibe modes, keeping the N_PWM_ANALOG and AC_COUPLE bits
+    /// consistent with each other.
+    pub fn set_input_signal(&mut self, signal: InputSignal) -> Result<(), DrvError<E>> {
+        let (analog, ac_couple) = match signal {
+            InputSignal::Pwm => (false, false),
+            InputSignal::Analog => (true, false),
+            InputSignal::AcCoupledAnalog => (true, true),
+        };
+        let mut control3 = Control3Reg(self.read(Register::Control3)?);
+        control3.set_n_pwm_analog(analog);
+        self.write(Register::Control3, control3.0)?;
+
+        let mut control1 = Control1Reg(self.read(Register::Control1)?);
+        control1.set_ac_couple(ac_couple);
+        self.write(Register::Control1, control1.0)
+    }
+
+    /// Selects `InputSignal::Analog` and sets the window of IN/TRIG
+    /// voltages, in millivolts, that `analog_input_mv` maps drive levels
+    /// onto.  The device's own reference is fixed at
+    /// `ANALOG_INPUT_FULL_SCALE_MV` and no register rescales it, so the
+    /// window must lie within 0-1.8 V; raising `min_mv` skips the noise
+    /// gate's dead zone and lowering `max_mv` caps the strongest drive.
+    /// A source with a wider range, such as a 3.3 V DAC, must be kept
+    /// below 1.8 V (see `analog_input_dac_code`) or divided down
+    /// externally.
+    /// Returns `DrvError::InvalidConfig`, without changing anything, if
+    /// the window is empty or exceeds the reference.
+    pub fn set_analog_input_scaling(
+        &mut self,
+        min_mv: u16,
+        max_mv: u16,
+    ) -> Result<(), DrvError<E>> {
+        if min_mv >= max_mv || max_mv > ANALOG_INPUT_FULL_SCALE_MV {
+            return Err(DrvError::InvalidConfig);
+        }
+        self.set_input_signal(InputSignal::Analog)?;
+        self.analog_input_range_mv = (min_mv, max_mv);
+        Ok(())
+    }
+
+    /// Returns the IN/TRIG voltage, in millivolts, for a drive `duty`
+    /// between 0 and 255, within the window set by
+    /// `set_analog_input_scaling`.  Defaults to the full 0-1.8 V range.
+    pub fn analog_input_mv(&self, duty: u8) -> u16 {
+        let (min_mv, max_mv) = self.analog_input_range_mv;
+        let span = u32::from(max_mv - min_mv) * u32::from(duty) / 255;
+        min_mv + span as u16
+    }
+
+    /// Returns the noise-gate threshold applied to PWM and analog inputs
+    pub fn get_noise_gate(&mut self) -> Result<NoiseGate, DrvError<E>> {
+        Ok(NoiseGate::from(
+            Control3Reg(self.read(Register::Control3)?).ng_thresh(),
+        ))
+    }
+
+    /// Sets the noise-gate threshold applied to PWM and analog inputs
+    pub fn set_noise_gate(&mut self, ng: NoiseGate) -> Result<(), DrvError<E>> {
+        let mut control3 = Control3Reg(self.read(Register::Control3)?);
+        control3.set_ng_thresh(ng as u8);
+        self.write(Register::Control3, control3.0)
+    }
+
+    /// Configures a PWM input with noise-gate threshold `ng` and enters
+    /// `Mode::PwmInputAndAnalogInput`.  The input type and threshold share
+    /// the Control3 register, so both are updated in a single write before
+    /// the mode changes, and the input is never interpreted with a stale
+    /// threshold.
+    pub fn set_mode_pwm_with_noise_gate(&mut self, ng: NoiseGate) -> Result<(), DrvError<E>> {
+        self.set_input_mode_with_noise_gate(false, ng)
+    }
+
+    /// Like `set_mode_pwm_with_noise_gate`, but configures a DC-coupled
+    /// analog input
+    pub fn set_mode_analog_with_noise_gate(&mut self, ng: NoiseGate) -> Result<(), DrvError<E>> {
+        self.set_input_mode_with_noise_gate(true, ng)
+    }
+
+    fn set_input_mode_with_noise_gate(
+        &mut self,
+        analog: bool,
+        ng: NoiseGate,
+    ) -> Result<(), DrvError<E>> {
+        let mut control1 = Control1Reg(self.read(Register::Control1)?);
+        if control1.ac_couple() {
+            control1.set_ac_couple(false);
+            self.write(Register::Control1, control1.0)?;
+        }
+
+        let mut control3 = Control3Reg(self.read(Register::Control3)?);
+        control3.set_n_pwm_analog(analog);
+        control3.set_ng_thresh(ng as u8);
+        self.write(Register::Control3, control3.0)?;
+
+        self.set_mode(Mode::PwmInputAndAnalogInput)
+    }
+
+    /// Checks that the AC_COUPLE and N_PWM_ANALOG bits make sense for
+    /// `mode`, returning `DrvError::InvalidConfig` if they don't.  The
+    /// common-mode drive is only meaningful for an AC-coupled analog input,
+    /// and audio-to-vibe requires exactly that.
+    fn check_input_config(&mut self, mode: Mode) -> Result<(), DrvError<E>> {
+        if !mode_uses_input(mode) {
+            return Ok(());
+        }
+        let control1 = Control1Reg(self.read(Register::Control1)?);
+        let control3 = Control3Reg(self.read(Register::Control3)?);
+        if input_config_valid(mode, control1, control3) {
+            Ok(())
+        } else {
+            Err(DrvError::InvalidConfig)
+        }
+    }
+
     /// This field is the entry point for real-time playback (RTP) data. The DRV2605
     /// playback engine drives the RTP_INPUT[7:0] value to the load when
     /// MODE[2:0] = 5 (RTP mode). The RTP_INPUT[7:0] value can be updated in
@@ -849,30 +3062,657 @@ where
     /// unsigned by the DATA_FORMAT_RTP bit in register 0x1D. When the
     /// haptic waveform is complete, the user can idle the device by setting
     /// MODE[2:0] = 0, or alternatively by setting STANDBY = 1.
-    pub fn set_realtime_playback_input(&mut self, value: i8) -> Result<(), E> {
+    pub fn set_realtime_playback_input(&mut self, value: i8) -> Result<(), DrvError<E>> {
         self.write(Register::RealTimePlaybackInput, value as u8)
     }
 
+    /// Selects open-loop (`true`) or closed-loop (`false`) drive for the
+    /// motor type currently selected in the feedback control register,
+    /// setting ERM_OPEN_LOOP or LRA_OPEN_LOOP accordingly.
+    pub fn set_open_loop(&mut self, enable: bool) -> Result<(), DrvError<E>> {
+        let lra = FeedbackControlReg(self.read(Register::FeedbackControl)?).n_erm_lra();
+        let mut control3 = Control3Reg(self.read(Register::Control3)?);
+        if lra {
+            control3.set_lra_open_loop(enable);
+        } else {
+            control3.set_erm_open_loop(enable);
+        }
+        self.write(Register::Control3, control3.0)
+    }
+
+    /// Returns true if the device is driving the currently selected motor
+    /// type open loop, by reading back ERM_OPEN_LOOP or LRA_OPEN_LOOP.
+    pub fn open_loop(&mut self) -> Result<bool, DrvError<E>> {
+        let lra = FeedbackControlReg(self.read(Register::FeedbackControl)?).n_erm_lra();
+        let control3 = Control3Reg(self.read(Register::Control3)?);
+        Ok(if lra {
+            control3.lra_open_loop()
+        } else {
+            control3.erm_open_loop()
+        })
+    }
+
+    /// Works out whether the device drives the actuator open or closed
+    /// loop in its current mode.  Auto calibration always runs closed
+    /// loop; otherwise the ERM_OPEN_LOOP or LRA_OPEN_LOOP bit applies,
+    /// depending on the motor type selected in the feedback control
+    /// register.  An LRA configured with LRA_AUTO_OPEN_LOOP may still fall
+    /// back to open loop during playback if it loses back-EMF lock, which
+    /// can't be seen from the registers.
+    pub fn effective_loop_mode(&mut self) -> Result<LoopMode, DrvError<E>> {
+        let mode = self.get_mode()?.try_mode().map_err(DrvError::UnknownMode)?;
+        if let Mode::AutoCalibration = mode {
+            return Ok(LoopMode::ClosedLoop);
+        }
+        Ok(if self.open_loop()? {
+            LoopMode::OpenLoop
+        } else {
+            LoopMode::ClosedLoop
+        })
+    }
+
+    /// Enables or disables the device's compensation for variation in the
+    /// supply voltage.  Disable it if compensation is already implemented
+    /// upstream.
+    pub fn set_supply_compensation(&mut self, enable: bool) -> Result<(), DrvError<E>> {
+        let mut control3 = Control3Reg(self.read(Register::Control3)?);
+        control3.set_supply_comp_dis(!enable);
+        self.write(Register::Control3, control3.0)
+    }
+
+    /// Returns true if supply compensation is enabled, in which case the
+    /// drive output should not vary with VDD.
+    pub fn supply_compensation_enabled(&mut self) -> Result<bool, DrvError<E>> {
+        Ok(!Control3Reg(self.read(Register::Control3)?).supply_comp_dis())
+    }
+
+    /// Drives the actuator in the given direction at `magnitude`, where 0
+    /// is no drive and 255 is full scale.  The RTP format is switched to
+    /// `RtpFormat::Signed` and the two's complement value written for you;
+    /// since the signed range is half the size, the magnitude loses its
+    /// least significant bit.  The device must be in
+    /// `Mode::RealTimePlayback` (see `start_rtp`).
+    pub fn set_rtp_directional(&mut self, magnitude: u8, forward: bool) -> Result<(), DrvError<E>> {
+        self.set_rtp_format(RtpFormat::Signed)?;
+        let value = (magnitude >> 1) as i8;
+        self.set_realtime_playback_input(if forward { value } else { -value })
+    }
+
+    /// Returns the raw RTP_INPUT[7:0] value; see `set_realtime_playback_input`
+    pub fn get_realtime_playback_input(&mut self) -> Result<u8, DrvError<E>> {
+        self.read(Register::RealTimePlaybackInput)
+    }
+
+    /// Tells the watchdog (see `with_max_continuous_drive_ms`) that the
+    /// task controlling continuous drive is still alive, arming it if
+    /// necessary.  `now_ms` is the caller's millisecond clock; it may wrap
+    /// around.
+    pub fn keepalive(&mut self, now_ms: u32) {
+        if self.max_continuous_drive_ms != 0 {
+            self.last_keepalive_ms = Some(now_ms);
+        }
+    }
+
+    /// Sets the unsigned real-time playback duty, as `set_rtp` does, and
+    /// arranges for `tick` to put the device into standby once the duty
+    /// has stayed at zero for longer than `idle_timeout_ms`.  A non-zero
+    /// duty cancels any pending standby.  `now_ms` is the caller's
+    /// millisecond clock; it may wrap around.
+    pub fn set_rtp_with_auto_standby(
+        &mut self,
+        duty: u8,
+        idle_timeout_ms: u16,
+        now_ms: u32,
+    ) -> Result<(), DrvError<E>> {
+        self.set_rtp(duty)?;
+        self.idle_standby_ms = idle_timeout_ms;
+        if duty != 0 {
+            self.idle_since_ms = None;
+        } else if self.idle_since_ms.is_none() {
+            self.idle_since_ms = Some(now_ms);
+        }
+        Ok(())
+    }
+
+    /// Checks the watchdog and the RTP idle timer, and should be called
+    /// periodically from somewhere that keeps running if the controlling
+    /// task hangs, such as a timer.  If the watchdog is armed and the last
+    /// `keepalive` is older than `max_continuous_drive_ms`, the RTP input
+    /// is zeroed and the watchdog is disarmed.  If the duty passed to
+    /// `set_rtp_with_auto_standby` has been zero for longer than its
+    /// `idle_timeout_ms`, the idle timer is cleared.  In either case the
+    /// device is put into standby and true is returned.
+    pub fn tick(&mut self, now_ms: u32) -> Result<bool, DrvError<E>> {
+        if let Some(last_ms) = self.last_keepalive_ms {
+            if now_ms.wrapping_sub(last_ms) > u32::from(self.max_continuous_drive_ms) {
+                self.last_keepalive_ms = None;
+                self.idle_since_ms = None;
+                self.write(Register::RealTimePlaybackInput, 0)?;
+                self.set_standby(true)?;
+                return Ok(true);
+            }
+        }
+        if let Some(idle_ms) = self.idle_since_ms {
+            if now_ms.wrapping_sub(idle_ms) > u32::from(self.idle_standby_ms) {
+                self.idle_since_ms = None;
+                self.set_standby(true)?;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Returns the current real-time playback value scaled according to
+    /// the configured `RtpFormat`: 0.0 to 1.0 for unsigned data, and -1.0
+    /// to 1.0 for signed data.
+    pub fn rtp_normalized(&mut self) -> Result<f32, DrvError<E>> {
+        let control3 = Control3Reg(self.read(Register::Control3)?);
+        let value = self.get_realtime_playback_input()?;
+        if control3.data_format_rtp() {
+            return Ok(f32::from(value) / 255.0);
+        }
+        let value = value as i8;
+        if value < 0 {
+            Ok(f32::from(value) / 128.0)
+        } else {
+            Ok(f32::from(value) / 127.0)
+        }
+    }
+
+    /// Sets the unsigned real-time playback duty, where 0 is no drive and
+    /// 255 is full-scale drive.  Returns `DrvError::FormatMismatch` if the
+    /// device is configured for `RtpFormat::Signed`, where the same byte
+    /// would be interpreted as a two's complement value and drive the motor
+    /// backwards or weakly; use `set_rtp_signed` in that case.
+    pub fn set_rtp(&mut self, duty: u8) -> Result<(), DrvError<E>> {
+        self.check_rtp_format(RtpFormat::Unsigned)?;
+        self.write(Register::RealTimePlaybackInput, duty)
+    }
+
+    /// Streams a precomputed envelope of unsigned RTP values, writing one
+    /// sample every `step_ms` and leaving the device driving the last one.
+    /// As with `set_rtp`, returns `DrvError::FormatMismatch` unless the
+    /// device is configured for `RtpFormat::Unsigned`; the device must
+    /// already be in `Mode::RealTimePlayback` (see `start_rtp`).
+    pub fn play_rtp_sequence<D: DelayMs<u16>>(
+        &mut self,
+        samples: &[u8],
+        step_ms: u16,
+        delay: &mut D,
+    ) -> Result<(), DrvError<E>> {
+        self.check_rtp_format(RtpFormat::Unsigned)?;
+        for (i, &duty) in samples.iter().enumerate() {
+            if i > 0 {
+                delay.delay_ms(step_ms);
+            }
+            self.write(Register::RealTimePlaybackInput, duty)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the signed real-time playback value, where 0 is no drive and
+    /// the sign selects the drive direction (for an LRA, 0 is mid-scale).
+    /// Returns `DrvError::FormatMismatch` if the device is configured for
+    /// `RtpFormat::Unsigned`; select the format with `start_rtp` or
+    /// `set_rtp_format`.
+    pub fn set_rtp_signed(&mut self, value: i8) -> Result<(), DrvError<E>> {
+        self.check_rtp_format(RtpFormat::Signed)?;
+        self.write(Register::RealTimePlaybackInput, value as u8)
+    }
+
+    /// Returns `DrvError::FormatMismatch` unless the device is configured
+    /// for `format`
+    fn check_rtp_format(&mut self, format: RtpFormat) -> Result<(), DrvError<E>> {
+        let unsigned = Control3Reg(self.read(Register::Control3)?).data_format_rtp();
+        match (format, unsigned) {
+            (RtpFormat::Unsigned, true) | (RtpFormat::Signed, false) => Ok(()),
+            _ => Err(DrvError::FormatMismatch),
+        }
+    }
+
+    /// Starts recording unsigned RTP writes into `buffer`.  Each value
+    /// written through the returned `RtpRecorder` is sent to the device and
+    /// stored along with the caller-supplied timestamp, so that the session
+    /// can be played back later with `replay_recording`.
+    pub fn record<'a, 'b>(&'a mut self, buffer: &'b mut [RtpSample]) -> RtpRecorder<'a, 'b, I2C> {
+        RtpRecorder {
+            driver: self,
+            buffer,
+            len: 0,
+        }
+    }
+
+    /// Plays back RTP values captured by an `RtpRecorder`, reproducing the
+    /// recorded spacing between them.  The device must already be in
+    /// unsigned real-time playback (see `start_rtp`), and is left driving
+    /// the final value.
+    pub fn replay_recording<D: DelayMs<u16>>(
+        &mut self,
+        samples: &[RtpSample],
+        delay: &mut D,
+    ) -> Result<(), DrvError<E>> {
+        self.check_rtp_format(RtpFormat::Unsigned)?;
+        let mut previous_ms = match samples.first() {
+            Some(sample) => sample.at_ms,
+            None => return Ok(()),
+        };
+        for sample in samples {
+            let mut wait_ms = sample.at_ms.wrapping_sub(previous_ms);
+            while wait_ms > 0 {
+                let step = wait_ms.min(u32::from(u16::MAX));
+                delay.delay_ms(step as u16);
+                wait_ms -= step;
+            }
+            self.write(Register::RealTimePlaybackInput, sample.duty)?;
+            previous_ms = sample.at_ms;
+        }
+        Ok(())
+    }
+
+    /// Selects how the RTP_INPUT[7:0] value is interpreted
+    pub fn set_rtp_format(&mut self, format: RtpFormat) -> Result<(), DrvError<E>> {
+        let mut control3 = Control3Reg(self.read(Register::Control3)?);
+        control3.set_data_format_rtp(match format {
+            RtpFormat::Signed => false,
+            RtpFormat::Unsigned => true,
+        });
+        self.write(Register::Control3, control3.0)
+    }
+
+    /// Configures and starts real-time playback in one call.
+    /// The data format and `initial_duty` are programmed before the device
+    /// is switched into `Mode::RealTimePlayback` and woken from standby, so
+    /// the actuator never drives a stale RTP value from a previous session.
+    /// `initial_duty` is written as-is and interpreted according to `format`
+    /// (for `RtpFormat::Signed`, pass the two's complement byte, e.g.
+    /// `-64i8 as u8`).  Subsequent values are written with `set_rtp` or
+    /// `set_rtp_signed` to match the format.
+    pub fn start_rtp(&mut self, format: RtpFormat, initial_duty: u8) -> Result<(), DrvError<E>> {
+        self.set_rtp_format(format)?;
+        self.write(Register::RealTimePlaybackInput, initial_duty)?;
+
+        let mut mode = ModeReg(self.read(Register::Mode)?);
+        mode.set_standby(false);
+        mode.set_mode(Mode::RealTimePlayback as u8);
+        self.write(Register::Mode, mode.0)
+    }
+
+    /// Sweeps the drive intensity smoothly from `from_intensity` to
+    /// `to_intensity` over `duration_ms` milliseconds, using unsigned
+    /// real-time playback.  The RTP value is updated at most once per
+    /// millisecond, and only when it changes.  The device is left in
+    /// `Mode::RealTimePlayback`, driving at `to_intensity`.
+    pub fn crossfade<D: DelayMs<u16>>(
+        &mut self,
+        from_intensity: u8,
+        to_intensity: u8,
+        duration_ms: u16,
+        delay: &mut D,
+    ) -> Result<(), DrvError<E>> {
+        self.start_rtp(RtpFormat::Unsigned, from_intensity)?;
+        let from = i32::from(from_intensity);
+        let span = i32::from(to_intensity) - from;
+        let steps = i32::from(duration_ms);
+        let mut current = from_intensity;
+        for step in 1..=steps {
+            delay.delay_ms(1);
+            let value = (from + span * step / steps) as u8;
+            if value != current {
+                self.write(Register::RealTimePlaybackInput, value)?;
+                current = value;
+            }
+        }
+        if current != to_intensity {
+            self.write(Register::RealTimePlaybackInput, to_intensity)?;
+        }
+        Ok(())
+    }
+
+    /// Spells out `text` in morse code using unsigned real-time playback,
+    /// driving at `intensity` for each dot and dash.  Timing follows the
+    /// usual conventions: a dot is one `unit_ms`, a dash three units, with
+    /// one unit between the symbols of a letter, three units between
+    /// letters and seven between words.  Letters, digits and spaces are
+    /// supported; any other character is skipped.  The device is left in
+    /// `Mode::RealTimePlayback` with no drive.
+    pub fn play_morse<D: DelayMs<u16>>(
+        &mut self,
+        text: &str,
+        unit_ms: u16,
+        intensity: u8,
+        delay: &mut D,
+    ) -> Result<(), DrvError<E>> {
+        let letter_gap_ms = unit_ms.saturating_mul(3);
+        let word_gap_ms = unit_ms.saturating_mul(7);
+        self.start_rtp(RtpFormat::Unsigned, 0)?;
+
+        // The silence owed before the next letter; zero at the start so
+        // that leading spaces don't delay the first letter.
+        let mut gap_ms = 0;
+        for c in text.chars() {
+            if c == ' ' {
+                if gap_ms > 0 {
+                    gap_ms = word_gap_ms;
+                }
+                continue;
+            }
+            let code = match morse_code(c) {
+                Some(code) => code,
+                None => continue,
+            };
+            if gap_ms > 0 {
+                delay.delay_ms(gap_ms);
+            }
+            for (i, symbol) in code.bytes().enumerate() {
+                if i > 0 {
+                    delay.delay_ms(unit_ms);
+                }
+                self.write(Register::RealTimePlaybackInput, intensity)?;
+                delay.delay_ms(if symbol == b'-' {
+                    letter_gap_ms
+                } else {
+                    unit_ms
+                });
+                self.write(Register::RealTimePlaybackInput, 0)?;
+            }
+            gap_ms = letter_gap_ms;
+        }
+        Ok(())
+    }
+
     /// This bit sets the output driver into a true high-impedance state. The device
     /// must be enabled to go into the high-impedance state. When in hardware
     /// shutdown or standby mode, the output drivers have 15 kΩ to ground. When
     /// the HI_Z bit is asserted, the hi-Z functionality takes effect immediately, even
     /// if a transaction is taking place.
-    pub fn set_high_impedance_state(&mut self, value: bool) -> Result<(), E> {
+    /// Returns `DrvError::InvalidConfig`, without changing anything, if
+    /// asked to enable hi-Z while the device is in standby.
+    pub fn set_high_impedance(&mut self, enable: bool) -> Result<(), DrvError<E>> {
+        if enable && ModeReg(self.read(Register::Mode)?).standby() {
+            return Err(DrvError::InvalidConfig);
+        }
         let mut register = RegisterThree(self.read(Register::Register3)?);
-        register.set_hi_z(value);
+        register.set_hi_z(enable);
         self.write(Register::Register3, register.0)
     }
 
+    /// Returns true if the HI_Z bit is set
+    pub fn high_impedance(&mut self) -> Result<bool, DrvError<E>> {
+        Ok(RegisterThree(self.read(Register::Register3)?).hi_z())
+    }
+
+    /// Halts playback by clearing the GO bit, which cancels a waveform
+    /// sequence, calibration or diagnostics in progress.  In
+    /// `Mode::RealTimePlayback` the motor is driven by the RTP input rather
+    /// than the GO bit, so the RTP input is also set to 0 to silence it.
+    /// The device stays awake and in its current mode; see
+    /// `emergency_stop` for a more drastic halt.
+    pub fn stop(&mut self) -> Result<(), DrvError<E>> {
+        let mode = self.get_mode()?.try_mode().map_err(DrvError::UnknownMode)?;
+        if let Mode::RealTimePlayback = mode {
+            self.write(Register::RealTimePlaybackInput, 0)?;
+        }
+        self.set_go(false)
+    }
+
+    /// Silences the output as quickly as possible, for use on faults and
+    /// other safety events.  The first write asserts HI_Z, which takes
+    /// effect immediately even if a transaction is taking place; the GO bit
+    /// is then cleared and the device placed in standby.
+    ///
+    /// To keep the first write unconditional, register 0x03 is overwritten
+    /// rather than modified, which also resets the library selection.
+    /// Clear HI_Z and call `set_library` again before resuming playback.
+    pub fn emergency_stop(&mut self) -> Result<(), DrvError<E>> {
+        let mut register = RegisterThree(0);
+        register.set_hi_z(true);
+        self.write(Register::Register3, register.0)?;
+        self.write(Register::Go, 0)?;
+        self.set_standby(true)
+    }
+
+    /// Puts the device into its lowest-power software state: the outputs
+    /// are switched to high impedance (which requires the device to be
+    /// active, so it is woken first if necessary) and then the device
+    /// enters standby.  Before waking, playback is cancelled, the RTP
+    /// input is zeroed and `Mode::InternalTrigger` is selected, so that a
+    /// stale RTP value or PWM/analog input can't drive the actuator while
+    /// HI_Z is being set.
+    /// This driver does not own the EN pin; for the lowest quiescent
+    /// current, drive EN low afterwards, which shuts the device down and
+    /// leaves it unreachable over I2C until EN is raised again.  See the
+    /// datasheet electrical characteristics for the standby and shutdown
+    /// currents.
+    ///
+    /// HI_Z stays set through standby, so to resume playback call
+    /// `set_standby(false)` and then `set_high_impedance(false)`, and
+    /// select the desired mode again with `set_mode`.
+    pub fn deep_sleep(&mut self) -> Result<(), DrvError<E>> {
+        self.write(Register::Go, 0)?;
+        self.write(Register::RealTimePlaybackInput, 0)?;
+        let mut mode = ModeReg(self.read(Register::Mode)?);
+        mode.set_mode(Mode::InternalTrigger as u8);
+        self.write(Register::Mode, mode.0)?;
+
+        self.set_standby(false)?;
+        self.set_high_impedance(true)?;
+        self.set_standby(true)
+    }
+
+    /// Sets up ROM library playback in one call: selects the feedback
+    /// algorithm for `motor`, drives ERMs open loop (which the ERM
+    /// libraries A through E are designed for) and LRAs closed loop (for
+    /// auto-resonance tracking), selects `library` and enters
+    /// `Mode::InternalTrigger`.  The standby bit is left unchanged.
+    /// Returns `DrvError::InvalidConfig` if the library doesn't match the
+    /// motor type, or is `Empty` or `Reserved`.
+    pub fn configure_rom(
+        &mut self,
+        library: LibrarySelection,
+        motor: MotorType,
+    ) -> Result<(), DrvError<E>> {
+        self.configure_rom_mode(library, motor, Mode::InternalTrigger)
+    }
+
+    /// Like `configure_rom`, but enters `Mode::ExternalTriggerRisingEdge`
+    /// so that a rising edge on the IN/TRIG pin plays the programmed
+    /// sequence without any bus traffic.  A second rising edge before the
+    /// sequence completes cancels it.
+    /// Program the sequence with `set_waveform` or `set_single_effect`.
+    pub fn set_mode_external_edge(
+        &mut self,
+        library: LibrarySelection,
+        motor: MotorType,
+    ) -> Result<(), DrvError<E>> {
+        self.configure_rom_mode(library, motor, Mode::ExternalTriggerRisingEdge)
+    }
+
+    /// Like `configure_rom`, but enters `Mode::ExternalTriggerLevelMode`,
+    /// where the GO bit follows the IN/TRIG pin: the sequence plays while
+    /// the pin is held high and is cancelled when it goes low.
+    /// Program the sequence with `set_waveform` or `set_single_effect`.
+    pub fn set_mode_external_level(
+        &mut self,
+        library: LibrarySelection,
+        motor: MotorType,
+    ) -> Result<(), DrvError<E>> {
+        self.configure_rom_mode(library, motor, Mode::ExternalTriggerLevelMode)
+    }
+
+    fn configure_rom_mode(
+        &mut self,
+        library: LibrarySelection,
+        motor: MotorType,
+        mode: Mode,
+    ) -> Result<(), DrvError<E>> {
+        match (library, motor) {
+            (LibrarySelection::A, MotorType::Erm)
+            | (LibrarySelection::B, MotorType::Erm)
+            | (LibrarySelection::C, MotorType::Erm)
+            | (LibrarySelection::D, MotorType::Erm)
+            | (LibrarySelection::E, MotorType::Erm)
+            | (LibrarySelection::LRA, MotorType::Lra) => {}
+            _ => return Err(DrvError::InvalidConfig),
+        }
+        let lra = motor == MotorType::Lra;
+
+        let mut feedback = FeedbackControlReg(self.read(Register::FeedbackControl)?);
+        feedback.set_n_erm_lra(lra);
+        self.write(Register::FeedbackControl, feedback.0)?;
+
+        let mut control3 = Control3Reg(self.read(Register::Control3)?);
+        control3.set_erm_open_loop(!lra);
+        control3.set_lra_open_loop(false);
+        self.write(Register::Control3, control3.0)?;
+
+        self.set_library(library)?;
+        self.set_mode(mode)
+    }
+
+    /// Helps pick a library by playing `effect` once in each of the ERM
+    /// libraries A through E, in order, pausing `per_library_ms` after each
+    /// one so the differences can be felt.  If the feedback control
+    /// register selects an LRA, only the LRA library is played.  The
+    /// previously selected library is restored afterwards.  The device
+    /// needs to be awake and in `Mode::InternalTrigger`.
+    pub fn library_audition<D: DelayMs<u16>>(
+        &mut self,
+        effect: Effect,
+        delay: &mut D,
+        per_library_ms: u16,
+    ) -> Result<(), DrvError<E>> {
+        const ERM_LIBRARIES: [LibrarySelection; 5] = [
+            LibrarySelection::A,
+            LibrarySelection::B,
+            LibrarySelection::C,
+            LibrarySelection::D,
+            LibrarySelection::E,
+        ];
+        let lra = FeedbackControlReg(self.read(Register::FeedbackControl)?).n_erm_lra();
+        let libraries: &[LibrarySelection] = if lra {
+            &[LibrarySelection::LRA]
+        } else {
+            &ERM_LIBRARIES
+        };
+
+        let previous = RegisterThree(self.read(Register::Register3)?);
+        self.set_single_effect(effect)?;
+        let mut result = Ok(());
+        for &library in libraries {
+            result = self
+                .set_library(library)
+                .and_then(|_| self.set_go(true))
+                .and_then(|_| self.wait_until_idle(delay, PLAYBACK_TIMEOUT_MS));
+            if result.is_err() {
+                break;
+            }
+            delay.delay_ms(per_library_ms);
+        }
+        let restored = self.write(Register::Register3, previous.0);
+        result.and(restored)
+    }
+
     /// Selects the library the playback engine selects when the GO bit is set.
-    pub fn set_library(&mut self, value: LibrarySelection) -> Result<(), E> {
+    pub fn set_library(&mut self, value: LibrarySelection) -> Result<(), DrvError<E>> {
         let mut register = RegisterThree(self.read(Register::Register3)?);
         register.set_library_selection(value as u8);
         self.write(Register::Register3, register.0)
     }
 
-    /// Sets the waveform generation registers to the shape provided
-    pub fn set_waveform(&mut self, waveform: &[WaveformReg; 8]) -> Result<(), E> {
+    /// Returns how far the overdrive clamp sits above the rated voltage, as
+    /// the difference between the OD_CLAMP[7:0] and RATED_VOLTAGE[7:0] codes
+    /// (zero if the clamp is at or below the rated voltage).
+    /// The device has no status bit that reports when the clamp is limiting
+    /// the automatic overdrive, but a small headroom means there is little
+    /// room for overdrive and effects may feel weak.  Both codes are roughly
+    /// 21 mV per step, but the exact scale depends on the motor type and
+    /// timing parameters; see the datasheet sections on Rated Voltage and
+    /// Overdrive Voltage-Clamp Programming.
+    pub fn clamp_voltage_headroom(&mut self) -> Result<u8, DrvError<E>> {
+        let rated = self.read(Register::RatedVoltage)?;
+        let clamp = self.read(Register::OverdriveClampVoltage)?;
+        Ok(clamp.saturating_sub(rated))
+    }
+
+    /// Selects the feedback gain ratio between braking gain and driving gain.
+    /// This value should be set prior to running auto calibration.
+    /// Use `BrakeFactor::Disabled` to turn off automatic braking entirely.
+    pub fn set_brake_factor(&mut self, factor: BrakeFactor) -> Result<(), DrvError<E>> {
+        let mut register = FeedbackControlReg(self.read(Register::FeedbackControl)?);
+        register.set_fb_brake_factor(factor as u8);
+        self.write(Register::FeedbackControl, register.0)
+    }
+
+    /// Selects the ERM (back-EMF) or LRA (auto-resonance) feedback algorithm
+    /// by writing only the `n_erm_lra` bit, leaving the rest of the feedback
+    /// control register untouched.
+    ///
+    /// The calibration results held by the device are specific to the motor
+    /// type, so auto calibration must be run (or `set_load_params` used)
+    /// after changing this.
+    pub fn set_feedback_motor_type(&mut self, lra: bool) -> Result<(), DrvError<E>> {
+        let mut register = FeedbackControlReg(self.read(Register::FeedbackControl)?);
+        register.set_n_erm_lra(lra);
+        self.write(Register::FeedbackControl, register.0)
+    }
+
+    /// Programs the auto-calibration inputs from `params`, selecting the
+    /// LRA or ERM feedback algorithm according to `lra`.
+    /// RATED_VOLTAGE and OD_CLAMP are written outright; the remaining
+    /// fields (N_ERM_LRA, FB_BRAKE_FACTOR, LOOP_GAIN, DRIVE_TIME and
+    /// AUTO_CAL_TIME) are updated in place so that the other settings in
+    /// their registers, such as BEMF_GAIN or AC_COUPLE, are preserved.
+    /// Returns `DrvError::InvalidParams` without writing anything if
+    /// `params` fails `CalibrationParams::validate`.
+    pub fn set_calibration_params(
+        &mut self,
+        params: &CalibrationParams,
+        lra: bool,
+    ) -> Result<(), DrvError<E>> {
+        params.validate().map_err(DrvError::InvalidParams)?;
+
+        let mut feedback = FeedbackControlReg(self.read(Register::FeedbackControl)?);
+        feedback.set_n_erm_lra(lra);
+        feedback.set_fb_brake_factor(params.brake_factor as u8);
+        feedback.set_loop_gain(params.loop_gain);
+        self.write(Register::FeedbackControl, feedback.0)?;
+
+        self.write(Register::RatedVoltage, params.rated)?;
+        self.write(Register::OverdriveClampVoltage, params.clamp)?;
+
+        let mut control1 = Control1Reg(self.read(Register::Control1)?);
+        control1.set_drive_time(params.drive_time);
+        self.write(Register::Control1, control1.0)?;
+
+        let mut control4 = Control4Reg(self.read(Register::Control4)?);
+        control4.set_auto_cal_time(params.auto_cal_time);
+        self.write(Register::Control4, control4.0)
+    }
+
+    /// Programs the sequencer from a `WaveformSequence`
+    pub fn set_sequence(&mut self, seq: &WaveformSequence) -> Result<(), DrvError<E>> {
+        self.set_waveform(seq.slots())
+    }
+
+    /// Programs up to eight sequencer slots from `entries`, followed by a
+    /// stop if there is room, so that effects and waits can be interleaved
+    /// freely.  Returns `DrvError::SequenceTooLong` for more than eight
+    /// entries.
+    pub fn set_sequence_entries(&mut self, entries: &[SequenceEntry]) -> Result<(), DrvError<E>> {
+        if entries.len() > 8 {
+            return Err(DrvError::SequenceTooLong);
+        }
+        let mut waveform = [WaveformReg::new_stop(); 8];
+        for (slot, &entry) in waveform.iter_mut().zip(entries.iter()) {
+            *slot = WaveformReg::from(entry);
+        }
+        self.set_waveform(&waveform)
+    }
+
+    /// Sets the waveform generation registers to the shape provided.
+    /// All eight slots are written in a single burst starting at register
+    /// 0x04.
+    pub fn set_waveform(&mut self, waveform: &[WaveformReg; 8]) -> Result<(), DrvError<E>> {
         let buf: [u8; 9] = [
             Register::WaveformSequence0 as u8,
             waveform[0].0,
@@ -884,16 +3724,241 @@ where
             waveform[6].0,
             waveform[7].0,
         ];
-        self.i2c.write(ADDRESS, &buf)
+        self.write_registers(&buf)
+    }
+
+    /// Programs the sequencer to wait `delay_ms` and then play `effect`,
+    /// and fires it, so that the device times the delay rather than the
+    /// caller.  The delay is rounded down to 10 ms and uses as many wait
+    /// slots as needed, each holding up to 1270 ms; delays that would leave
+    /// no slot for the effect (beyond 8890 ms) return
+    /// `DrvError::SequenceTooLong`.  The device must be in
+    /// `Mode::InternalTrigger` and out of standby.
+    pub fn schedule_effect(&mut self, effect: Effect, delay_ms: u16) -> Result<(), DrvError<E>> {
+        let mut waveform = [WaveformReg::new_stop(); 8];
+        let mut remaining = delay_ms / 10;
+        let mut slot = 0;
+        while remaining > 0 {
+            if slot == waveform.len() - 1 {
+                return Err(DrvError::SequenceTooLong);
+            }
+            let wait = remaining.min(0x7f);
+            waveform[slot] = WaveformReg::new_wait_time(wait as u8);
+            remaining -= wait;
+            slot += 1;
+        }
+        waveform[slot] = WaveformReg::new_effect(effect);
+        self.set_waveform(&waveform)?;
+        self.replay()
+    }
+
+    /// Reads back the contents of the eight waveform sequencer registers
+    pub fn get_waveform(&mut self) -> Result<[WaveformReg; 8], DrvError<E>> {
+        let mut buf = [0u8; 8];
+        self.read_registers(Register::WaveformSequence0, &mut buf)?;
+        let mut waveform = [WaveformReg::new_stop(); 8];
+        for (slot, &value) in waveform.iter_mut().zip(buf.iter()) {
+            *slot = WaveformReg(value);
+        }
+        Ok(waveform)
+    }
+
+    /// Returns how many effects the programmed sequence will play before it
+    /// reaches the stop terminator (or the end of the eight slots).
+    /// Wait slots are skipped rather than counted.
+    pub fn sequence_length(&mut self) -> Result<usize, DrvError<E>> {
+        let waveform = self.get_waveform()?;
+        Ok(waveform
+            .iter()
+            .take_while(|slot| slot.0 != 0)
+            .filter(|slot| !slot.wait())
+            .count())
+    }
+
+    /// Reads back and decodes the programmed sequence.  Slots from the stop
+    /// terminator onwards are `None`.
+    pub fn sequence_effects(&mut self) -> Result<[Option<SequenceEntry>; 8], DrvError<E>> {
+        let waveform = self.get_waveform()?;
+        let mut entries = [None; 8];
+        for (entry, slot) in entries.iter_mut().zip(waveform.iter()) {
+            match slot.entry() {
+                Some(decoded) => *entry = Some(decoded),
+                None => break,
+            }
+        }
+        Ok(entries)
     }
 
-    pub fn set_single_effect(&mut self, effect: Effect) -> Result<(), E> {
+    pub fn set_single_effect(&mut self, effect: Effect) -> Result<(), DrvError<E>> {
         let buf: [u8; 3] = [
             Register::WaveformSequence0 as u8,
             WaveformReg::new_effect(effect).0,
             WaveformReg::new_stop().0,
         ];
-        self.i2c.write(ADDRESS, &buf)
+        self.write_registers(&buf)
+    }
+
+    /// Plays the smooth ROM transition ramp selected by `direction`,
+    /// `speed` and `range` (see `Effect::transition_ramp`).  The device
+    /// needs to be awake and in `Mode::InternalTrigger`.
+    pub fn ramp(
+        &mut self,
+        direction: RampDirection,
+        speed: RampSpeed,
+        range: RampRange,
+    ) -> Result<(), DrvError<E>> {
+        self.set_single_effect(Effect::transition_ramp(direction, speed, range))?;
+        self.replay()
+    }
+
+    /// Plays an arbitrarily long list of effects by splitting it into groups
+    /// of eight, the size of the hardware sequencer.  Each group is
+    /// programmed and fired in turn, waiting for the GO bit to clear before
+    /// moving on to the next one.  The device needs to be awake and in
+    /// `Mode::InternalTrigger`.
+    pub fn play_long_sequence<D: DelayMs<u16>>(
+        &mut self,
+        effects: &[Effect],
+        delay: &mut D,
+    ) -> Result<(), DrvError<E>> {
+        for chunk in effects.chunks(8) {
+            self.set_effects(chunk)?;
+            self.set_go(true)?;
+            self.wait_until_idle(delay, PLAYBACK_TIMEOUT_MS)?;
+        }
+        Ok(())
+    }
+
+    /// Programs up to eight effects, fires them, and polls the GO bit once
+    /// per millisecond until playback completes, returning the measured
+    /// playback time in milliseconds.  The measurement has 1 ms resolution
+    /// plus the I2C time of each poll, which makes it suitable for checking
+    /// nominal effect durations against a particular motor and library.
+    /// Returns `DrvError::Timeout` if playback takes longer than 10
+    /// seconds.
+    pub fn play_sequence_timed<D: DelayMs<u16>>(
+        &mut self,
+        effects: &[Effect],
+        delay: &mut D,
+    ) -> Result<u32, DrvError<E>> {
+        self.set_effects(effects)?;
+        self.set_go(true)?;
+        let mut elapsed = 0u32;
+        while self.get_go()?.go() {
+            if elapsed >= u32::from(PLAYBACK_TIMEOUT_MS) {
+                return Err(DrvError::Timeout);
+            }
+            delay.delay_ms(1);
+            elapsed += 1;
+        }
+        Ok(elapsed)
+    }
+
+    /// Programs up to eight effects into the sequencer, followed by a stop
+    fn set_effects(&mut self, effects: &[Effect]) -> Result<(), DrvError<E>> {
+        if effects.len() > 8 {
+            return Err(DrvError::SequenceTooLong);
+        }
+        let mut waveform = [WaveformReg::new_stop(); 8];
+        for (slot, &effect) in waveform.iter_mut().zip(effects.iter()) {
+            *slot = WaveformReg::new_effect(effect);
+        }
+        self.set_waveform(&waveform)
+    }
+
+    /// Programs up to eight effects into the sequencer, each substituted
+    /// with its variant closest to `level` (see `Effect::at_intensity`).
+    /// This provides a global softening control for ROM effects, which
+    /// can't be scaled the way an RTP duty can.
+    pub fn set_sequence_scaled(
+        &mut self,
+        effects: &[Effect],
+        level: IntensityLevel,
+    ) -> Result<(), DrvError<E>> {
+        if effects.len() > 8 {
+            return Err(DrvError::SequenceTooLong);
+        }
+        let mut scaled = [Effect::StrongClick100; 8];
+        for (slot, effect) in scaled.iter_mut().zip(effects.iter()) {
+            *slot = effect.at_intensity(level);
+        }
+        self.set_effects(&scaled[..effects.len()])
+    }
+
+    /// Plays `effect` to completion with the overdrive clamp temporarily
+    /// raised to `boost_clamp`, for an alert that should feel noticeably
+    /// stronger than regular feedback.  Pick `boost_clamp` with
+    /// `lra_overdrive_clamp_reg` or `erm_overdrive_clamp_reg` so that it
+    /// stays within the actuator's rating; 0xff is over 5 V peak.
+    /// Returns `DrvError::InvalidConfig`, without changing anything, if
+    /// `boost_clamp` is below the current clamp.
+    /// The previous clamp is restored afterwards, even if playback fails;
+    /// if playback doesn't finish in time it is cancelled first.  The
+    /// device needs to be awake and in `Mode::InternalTrigger`; the
+    /// sequencer is left holding `effect`.
+    pub fn play_boosted<D: DelayMs<u16>>(
+        &mut self,
+        effect: Effect,
+        boost_clamp: u8,
+        delay: &mut D,
+    ) -> Result<(), DrvError<E>> {
+        let clamp = self.read(Register::OverdriveClampVoltage)?;
+        if boost_clamp < clamp {
+            return Err(DrvError::InvalidConfig);
+        }
+        self.write(Register::OverdriveClampVoltage, boost_clamp)?;
+
+        let result = self
+            .set_single_effect(effect)
+            .and_then(|_| self.set_go(true))
+            .and_then(|_| self.wait_until_idle(delay, PLAYBACK_TIMEOUT_MS));
+        let stopped = match result {
+            Ok(()) => Ok(()),
+            Err(_) => self.set_go(false),
+        };
+
+        let restored = self.write(Register::OverdriveClampVoltage, clamp);
+        result.and(stopped).and(restored)
+    }
+
+    /// Blocks until the process fired by the GO bit (a waveform sequence,
+    /// auto calibration or diagnostics) completes.  Polls the GO bit once
+    /// per millisecond until it self-clears, returning `DrvError::Timeout`
+    /// if it is still set after `timeout_ms`.
+    pub fn wait_until_idle<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u16,
+    ) -> Result<(), DrvError<E>> {
+        let mut elapsed = 0;
+        while self.get_go()?.go() {
+            if elapsed >= timeout_ms {
+                return Err(DrvError::Timeout);
+            }
+            delay.delay_ms(1);
+            elapsed += 1;
+        }
+        Ok(())
+    }
+
+    /// Arms the device so that holding the IN/TRIG pin high drives the
+    /// actuator continuously and releasing it stops the drive.
+    /// All eight waveform slots are filled with `effect` (there is no stop
+    /// terminator) and the device is woken up in
+    /// `Mode::ExternalTriggerLevelMode`, where the GO bit follows the pin.
+    /// The sequencer still ends after the eighth slot, so short effects will
+    /// not sustain for long; `Effect::LongBuzzForProgrammaticStopping100` is
+    /// intended for exactly this use and keeps driving until the level
+    /// trigger cancels it.
+    pub fn arm_continuous_on_level(&mut self, effect: Effect) -> Result<(), DrvError<E>> {
+        let slot = WaveformReg::new_effect(effect);
+        self.set_waveform(&[slot, slot, slot, slot, slot, slot, slot, slot])?;
+
+        self.check_input_config(Mode::ExternalTriggerLevelMode)?;
+        let mut mode = ModeReg(self.read(Register::Mode)?);
+        mode.set_standby(false);
+        mode.set_mode(Mode::ExternalTriggerLevelMode as u8);
+        self.write(Register::Mode, mode.0)
     }
 
     /// This bit is used to fire processes in the DRV2605 device. The process
@@ -906,12 +3971,112 @@ where
     /// waveform sequence. Using one of the external trigger modes can cause
     /// the GO bit to be set or cleared by the external trigger pin. This bit can also
     /// be used to fire the auto-calibration process or the diagnostic process.
-    pub fn set_go(&mut self, go: bool) -> Result<(), E> {
+    /// This does not enforce `with_min_retrigger_ms`; use `trigger_at` for
+    /// that.
+    pub fn set_go(&mut self, go: bool) -> Result<(), DrvError<E>> {
         let mut register = GoReg(self.read(Register::Go)?);
         register.set_go(go);
         self.write(Register::Go, register.0)
     }
 
+    /// Fires the currently programmed sequence again by setting the GO bit,
+    /// without re-programming the sequencer.  This assumes that the sequence
+    /// and mode are unchanged since they were last set up, and costs a
+    /// single register write per repeat.
+    /// This does not enforce `with_min_retrigger_ms`; use `trigger_at` for
+    /// that.
+    pub fn replay(&mut self) -> Result<(), DrvError<E>> {
+        let mut register = GoReg(0);
+        register.set_go(true);
+        self.write(Register::Go, register.0)
+    }
+
+    /// Sets the GO bit and blocks until the fired process completes, or
+    /// returns `DrvError::Timeout` after `timeout_ms`.  See
+    /// `wait_until_idle`.
+    pub fn trigger_and_wait<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u16,
+    ) -> Result<(), DrvError<E>> {
+        self.set_go(true)?;
+        self.wait_until_idle(delay, timeout_ms)
+    }
+
+    /// Fires the programmed sequence like `replay`, and returns a
+    /// `CompletionWaiter` that expects it to finish `duration_ms` after
+    /// `now_ms`.
+    pub fn go_with_completion(
+        &mut self,
+        now_ms: u32,
+        duration_ms: u32,
+    ) -> Result<CompletionWaiter, DrvError<E>> {
+        self.replay()?;
+        Ok(CompletionWaiter::new(now_ms, duration_ms))
+    }
+
+    /// Like `replay`, but returns `DrvError::TooSoon` without touching the
+    /// device if fewer than `min_retrigger_ms` milliseconds have passed
+    /// since the last successful trigger.  `now_ms` is the caller's
+    /// millisecond clock; it may wrap around.
+    pub fn trigger_at(&mut self, now_ms: u32) -> Result<(), DrvError<E>> {
+        if let Some(last_ms) = self.last_trigger_ms {
+            let elapsed = now_ms.wrapping_sub(last_ms);
+            if elapsed < u32::from(self.min_retrigger_ms) {
+                return Err(DrvError::TooSoon {
+                    remaining_ms: (u32::from(self.min_retrigger_ms) - elapsed) as u16,
+                });
+            }
+        }
+        self.replay()?;
+        self.last_trigger_ms = Some(now_ms);
+        Ok(())
+    }
+
+    /// Sets the GO bit and checks for a fault shortly after the drive
+    /// starts, catching a shorted actuator on the very first drive rather
+    /// than after a full playback.  If the status register reports an
+    /// over-current or over-temperature condition 5 ms after firing, the
+    /// playback is cancelled and `DrvError::Fault` is returned.
+    pub fn fire_and_check<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<(), DrvError<E>> {
+        self.set_go(true)?;
+        delay.delay_ms(5);
+        let status = self.get_status()?;
+        if status.oc_detected() || status.over_temp() {
+            self.set_go(false)?;
+            return Err(DrvError::Fault(status));
+        }
+        Ok(())
+    }
+
+    pub fn get_go(&mut self) -> Result<GoReg, DrvError<E>> {
+        self.read(Register::Go).map(GoReg)
+    }
+
+    /// Counts the rising edges seen on the IN/TRIG pin over `window_ms`
+    /// milliseconds, which can be used to recognize taps and double taps
+    /// from a button or piezo wired to that pin.
+    /// The device must already be in `Mode::ExternalTriggerLevelMode`, where
+    /// the GO bit mirrors the pin; it is sampled once per millisecond, so
+    /// pulses shorter than that (plus the bus transaction time) can be missed.
+    pub fn detect_taps<D: DelayMs<u16>>(
+        &mut self,
+        window_ms: u16,
+        delay: &mut D,
+    ) -> Result<u8, DrvError<E>> {
+        let mut taps = 0u8;
+        let mut previous = self.get_go()?.go();
+        for _ in 0..window_ms {
+            delay.delay_ms(1);
+            let current = self.get_go()?.go();
+            if current && !previous {
+                taps = taps.saturating_add(1);
+            }
+            previous = current;
+        }
+        Ok(taps)
+    }
+
     /// This bit adds a time offset to the overdrive portion of the library
     /// waveforms. Some motors require more overdrive time than others, so this
     /// register allows the user to add or remove overdrive time from the library
@@ -922,10 +4087,15 @@ where
     /// positive or negative.
     /// Overdrive Time Offset (ms) = ODT[7:0] × PLAYBACK_INTERVAL
     /// See the section for PLAYBACK_INTERVAL details.
-    pub fn set_overdrive_time_offset(&mut self, value: i8) -> Result<(), E> {
+    pub fn set_overdrive_time_offset(&mut self, value: i8) -> Result<(), DrvError<E>> {
         self.write(Register::OverdriveTimeOffset, value as u8)
     }
 
+    /// Returns the overdrive time offset, in units of PLAYBACK_INTERVAL
+    pub fn get_overdrive_time_offset(&mut self) -> Result<i8, DrvError<E>> {
+        Ok(self.read(Register::OverdriveTimeOffset)? as i8)
+    }
+
     /// This bit adds a time offset to the positive sustain portion of the library
     /// waveforms. Some motors have a faster or slower response time than
     /// others, so this register allows the user to add or remove positive sustain
@@ -934,10 +4104,16 @@ where
     /// interpreted as 2s complement, so the time offset can positive or negative.
     /// Sustain-Time Positive Offset (ms) = SPT[7:0] × PLAYBACK_INTERVAL
     /// See the section for PLAYBACK_INTERVAL details.
-    pub fn set_sustain_time_offset_positive(&mut self, value: i8) -> Result<(), E> {
+    /// Like the other time offsets, this is only useful in open-loop mode.
+    pub fn set_sustain_time_offset_positive(&mut self, value: i8) -> Result<(), DrvError<E>> {
         self.write(Register::SustainTimeOffsetPositive, value as u8)
     }
 
+    /// Returns the positive sustain time offset, in units of PLAYBACK_INTERVAL
+    pub fn get_sustain_time_offset_positive(&mut self) -> Result<i8, DrvError<E>> {
+        Ok(self.read(Register::SustainTimeOffsetPositive)? as i8)
+    }
+
     /// This bit adds a time offset to the negative sustain portion of the library
     /// waveforms. Some motors have a faster or slower response time than
     /// others, so this register allows the user to add or remove negative sustain
@@ -947,10 +4123,16 @@ where
     /// negative.
     /// Sustain-Time Negative Offset (ms) = SNT[7:0] × PLAYBACK_INTERVAL
     /// See the section for PLAYBACK_INTERVAL details.
-    pub fn set_sustain_time_offset_negative(&mut self, value: i8) -> Result<(), E> {
+    /// Like the other time offsets, this is only useful in open-loop mode.
+    pub fn set_sustain_time_offset_negative(&mut self, value: i8) -> Result<(), DrvError<E>> {
         self.write(Register::SustainTimeOffsetNegative, value as u8)
     }
 
+    /// Returns the negative sustain time offset, in units of PLAYBACK_INTERVAL
+    pub fn get_sustain_time_offset_negative(&mut self) -> Result<i8, DrvError<E>> {
+        Ok(self.read(Register::SustainTimeOffsetNegative)? as i8)
+    }
+
     /// This bit adds a time offset to the braking portion of the library waveforms.
     /// Some motors require more braking time than others, so this register allows
     /// the user to add or take away brake time from the library waveforms. The
@@ -960,7 +4142,959 @@ where
     /// 2s complement, so the time offset can be positive or negative.
     /// Brake Time Offset (ms) = BRT[7:0] × PLAYBACK_INTERVAL
     /// See the section for PLAYBACK_INTERVAL details.
-    pub fn set_brake_time_offset(&mut self, value: i8) -> Result<(), E> {
+    pub fn set_brake_time_offset(&mut self, value: i8) -> Result<(), DrvError<E>> {
         self.write(Register::BrakeTimeOffset, value as u8)
     }
+
+    /// Returns the brake time offset, in units of PLAYBACK_INTERVAL
+    pub fn get_brake_time_offset(&mut self) -> Result<i8, DrvError<E>> {
+        Ok(self.read(Register::BrakeTimeOffset)? as i8)
+    }
+
+    /// Captures the time offsets, feedback control and Control1-3 registers
+    /// so that a known-good tuning can be saved and later restored with
+    /// `apply_tuning`, or compared against another snapshot.
+    pub fn tuning_snapshot(&mut self) -> Result<TuningSnapshot, DrvError<E>> {
+        let mut offsets = [0u8; 4];
+        self.read_registers(Register::OverdriveTimeOffset, &mut offsets)?;
+        let mut controls = [0u8; 4];
+        self.read_registers(Register::FeedbackControl, &mut controls)?;
+        Ok(TuningSnapshot {
+            overdrive_time_offset: offsets[0] as i8,
+            sustain_time_offset_positive: offsets[1] as i8,
+            sustain_time_offset_negative: offsets[2] as i8,
+            brake_time_offset: offsets[3] as i8,
+            feedback: FeedbackControlReg(controls[0]),
+            control1: Control1Reg(controls[1]),
+            control2: Control2Reg(controls[2]),
+            control3: Control3Reg(controls[3]),
+        })
+    }
+
+    /// Writes back a tuning previously captured by `tuning_snapshot`
+    pub fn apply_tuning(&mut self, tuning: &TuningSnapshot) -> Result<(), DrvError<E>> {
+        let offsets: [u8; 5] = [
+            Register::OverdriveTimeOffset as u8,
+            tuning.overdrive_time_offset as u8,
+            tuning.sustain_time_offset_positive as u8,
+            tuning.sustain_time_offset_negative as u8,
+            tuning.brake_time_offset as u8,
+        ];
+        self.write_registers(&offsets)?;
+
+        let controls: [u8; 5] = [
+            Register::FeedbackControl as u8,
+            tuning.feedback.0,
+            tuning.control1.0,
+            tuning.control2.0,
+            tuning.control3.0,
+        ];
+        self.write_registers(&controls)
+    }
+
+    /// Stretches (`factor > 1.0`) or compresses (`factor < 1.0`) the timing
+    /// of `effect` by programming the overdrive, positive sustain, negative
+    /// sustain and brake time offsets.  The library's phase timing isn't
+    /// published, so the effect's `approx_duration_ms` is split between
+    /// the phases (one eighth each for overdrive and brake, three eighths
+    /// for each sustain phase) and each offset is set to the change in
+    /// that phase's length, in playback intervals, rounded and clamped to
+    /// the `i8` range.  The offsets apply to every library effect, so
+    /// re-run this when switching to an effect of a different length.
+    ///
+    /// The offsets only take effect in open loop mode; returns
+    /// `DrvError::InvalidConfig`, without changing anything, if the device
+    /// is driving closed loop.
+    pub fn scale_effect_timing(&mut self, effect: Effect, factor: f32) -> Result<(), DrvError<E>> {
+        if self.effective_loop_mode()? == LoopMode::ClosedLoop {
+            return Err(DrvError::InvalidConfig);
+        }
+        let interval_ms = f32::from(self.playback_interval_ms()?);
+        let duration_ms = f32::from(effect.approx_duration_ms());
+        let phase_eighths: [f32; 4] = [1.0, 3.0, 3.0, 1.0];
+
+        let mut offsets = [0u8; 5];
+        offsets[0] = Register::OverdriveTimeOffset as u8;
+        for (offset, eighths) in offsets[1..].iter_mut().zip(phase_eighths.iter()) {
+            let phase_ms = duration_ms * eighths / 8.0;
+            let scaled = (factor - 1.0) * phase_ms / interval_ms;
+            let scaled = if scaled >= 127.0 {
+                127
+            } else if scaled <= -128.0 {
+                -128
+            } else if scaled >= 0.0 {
+                (scaled + 0.5) as i8
+            } else {
+                (scaled - 0.5) as i8
+            };
+            *offset = scaled as u8;
+        }
+        self.write_registers(&offsets)
+    }
+
+    /// Returns the playback interval in milliseconds (5 or 1), the unit of
+    /// the waveform library timing and of the time offset registers
+    pub fn playback_interval_ms(&mut self) -> Result<u8, DrvError<E>> {
+        let control5 = Control5Reg(self.read(Register::Control5)?);
+        Ok(if control5.playback_interval() { 1 } else { 5 })
+    }
+
+    /// Trades waveform fidelity for the shortest delay between GO and a
+    /// perceptible vibration: selects the 1 ms playback interval, disables
+    /// the startup boost, and selects a fast-rise library for the motor
+    /// type currently selected in the feedback control register (library B
+    /// for ERMs, the LRA library otherwise).  Note that the playback
+    /// interval is also the time step of the library waveforms, so effects
+    /// become shorter overall.
+    pub fn configure_low_latency(&mut self) -> Result<(), DrvError<E>> {
+        let mut control5 = Control5Reg(self.read(Register::Control5)?);
+        control5.set_playback_interval(true);
+        self.write(Register::Control5, control5.0)?;
+
+        let mut control1 = Control1Reg(self.read(Register::Control1)?);
+        control1.set_startup_boost(false);
+        self.write(Register::Control1, control1.0)?;
+
+        let lra = FeedbackControlReg(self.read(Register::FeedbackControl)?).n_erm_lra();
+        self.set_library(if lra {
+            LibrarySelection::LRA
+        } else {
+            LibrarySelection::B
+        })
+    }
+
+    /// Returns the brake time offset in milliseconds: the BrakeTimeOffset
+    /// register multiplied by the current playback interval.  This is the
+    /// amount by which open-loop library effects' braking is lengthened
+    /// (or shortened, when negative).
+    pub fn brake_time_ms(&mut self) -> Result<f32, DrvError<E>> {
+        let offset = self.read(Register::BrakeTimeOffset)? as i8;
+        let interval = self.playback_interval_ms()?;
+        Ok(f32::from(offset) * f32::from(interval))
+    }
+
+    /// Reads all four time offset registers in one burst and returns them
+    /// in milliseconds, scaled by the current PLAYBACK_INTERVAL
+    pub fn time_offsets_ms(&mut self) -> Result<TimeOffsetsMs, DrvError<E>> {
+        let mut offsets = [0u8; 4];
+        self.read_registers(Register::OverdriveTimeOffset, &mut offsets)?;
+        let interval = i16::from(self.playback_interval_ms()?);
+        let scale = |value: u8| i16::from(value as i8) * interval;
+        Ok(TimeOffsetsMs {
+            overdrive: scale(offsets[0]),
+            sustain_positive: scale(offsets[1]),
+            sustain_negative: scale(offsets[2]),
+            brake: scale(offsets[3]),
+        })
+    }
+
+    /// Returns true if the one-time programmable memory has been programmed
+    pub fn otp_programmed(&mut self) -> Result<bool, DrvError<E>> {
+        Ok(Control4Reg(self.read(Register::Control4)?).otp_status())
+    }
+
+    /// Burns the contents of registers 0x16 through 0x1A (rated voltage,
+    /// overdrive clamp, calibration results and feedback control) into the
+    /// nonvolatile OTP memory.  This can only be done once per device, and
+    /// the supply must be at the programming voltage given in the datasheet
+    /// section on Programming On-Chip OTP Memory.
+    /// Waits for the OTP_PROGRAM bit to clear and OTP_STATUS to be set,
+    /// returning `DrvError::Timeout` if that doesn't happen, or
+    /// `DrvError::OtpAlreadyProgrammed` without doing anything if the memory
+    /// was programmed previously.
+    pub fn program_otp<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<(), DrvError<E>> {
+        let mut control4 = Control4Reg(self.read(Register::Control4)?);
+        if control4.otp_status() {
+            return Err(DrvError::OtpAlreadyProgrammed);
+        }
+        control4.set_otp_program(true);
+        self.write(Register::Control4, control4.0)?;
+
+        let mut elapsed = 0;
+        loop {
+            let control4 = Control4Reg(self.read(Register::Control4)?);
+            if !control4.otp_program() && control4.otp_status() {
+                return Ok(());
+            }
+            if elapsed >= self.poll_timeout_ms {
+                return Err(DrvError::Timeout);
+            }
+            delay.delay_ms(1);
+            elapsed += 1;
+        }
+    }
+
+    /// Reads every register in one burst and decodes them.
+    /// Note that reading the status register clears its latching flags.
+    pub fn read_state(&mut self) -> Result<DeviceState, DrvError<E>> {
+        let mut regs = [0u8; REGISTER_COUNT];
+        self.read_registers(Register::Status, &mut regs)?;
+
+        let mut waveform = [WaveformReg::new_stop(); 8];
+        for (slot, &value) in waveform.iter_mut().zip(regs[4..12].iter()) {
+            *slot = WaveformReg(value);
+        }
+
+        Ok(DeviceState {
+            status: StatusReg(regs[0x00]),
+            mode: ModeReg(regs[0x01]),
+            rtp_input: regs[0x02],
+            register3: RegisterThree(regs[0x03]),
+            waveform,
+            go: GoReg(regs[0x0c]),
+            overdrive_time_offset: regs[0x0d] as i8,
+            sustain_time_offset_positive: regs[0x0e] as i8,
+            sustain_time_offset_negative: regs[0x0f] as i8,
+            brake_time_offset: regs[0x10] as i8,
+            audio_to_vibe_control: regs[0x11],
+            audio_to_vibe_min_input_level: regs[0x12],
+            audio_to_vibe_max_input_level: regs[0x13],
+            audio_to_vibe_min_output_drive: regs[0x14],
+            audio_to_vibe_max_output_drive: regs[0x15],
+            rated_voltage: regs[0x16],
+            overdrive_clamp_voltage: regs[0x17],
+            auto_calibration_compensation_result: regs[0x18],
+            auto_calibration_back_emf_result: regs[0x19],
+            feedback: FeedbackControlReg(regs[0x1a]),
+            control1: Control1Reg(regs[0x1b]),
+            control2: Control2Reg(regs[0x1c]),
+            control3: Control3Reg(regs[0x1d]),
+            control4: Control4Reg(regs[0x1e]),
+            control5: Control5Reg(regs[0x1f]),
+            lra_open_loop_period: regs[0x20],
+            vbat: regs[0x21],
+            lra_resonance_period: regs[0x22],
+        })
+    }
+
+    /// Runs the auto-calibration routine using the inputs currently
+    /// programmed into the device (see `set_calibration_params`) and returns
+    /// the results.  The mode register is restored to its previous value
+    /// afterwards, whether or not calibration succeeded.  If enabled by
+    /// `with_warmup`, the warm-up hum is played before the mode is restored;
+    /// the sequencer slots it uses are restored too.
+    /// Returns `DrvError::CalibrationFailed` if the routine did not converge,
+    /// or `DrvError::Timeout` if it did not finish within the poll timeout.
+    /// The GO bit is polled in 1 ms steps rather than spun on, and the
+    /// budget is never less than the maximum duration of the programmed
+    /// AUTO_CAL_TIME, so that lowering the poll timeout can't make an
+    /// otherwise healthy calibration time out.
+    pub fn calibrate<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<LoadParams, DrvError<E>> {
+        let auto_cal_max_ms = match Control4Reg(self.read(Register::Control4)?).auto_cal_time() {
+            0 => 350,
+            1 => 450,
+            2 => 700,
+            _ => 1200,
+        };
+        let timeout_ms = self.poll_timeout_ms.max(auto_cal_max_ms);
+
+        let previous = self.read(Register::Mode)?;
+        let mut mode = ModeReg(previous);
+        mode.set_standby(false);
+        mode.set_mode(Mode::AutoCalibration as u8);
+        self.write(Register::Mode, mode.0)?;
+
+        let result = self
+            .set_go(true)
+            .and_then(|_| self.wait_until_idle(delay, timeout_ms))
+            .and_then(|_| self.get_status());
+        let passed = match result {
+            Ok(ref status) => {
+                let passed = !status.diagnostic_result();
+                self.last_calibration = Some(passed);
+                passed
+            }
+            Err(_) => false,
+        };
+        let result = match result {
+            Ok(status) if passed && self.warmup => self.warm_up(delay).map(|_| status),
+            other => other,
+        };
+
+        self.write(Register::Mode, previous)?;
+        result?;
+        if !passed {
+            return Err(DrvError::CalibrationFailed);
+        }
+        self.get_load_params()
+    }
+
+    /// Plays the settling hum requested by `with_warmup` and waits for it
+    /// to finish, restoring the two sequencer slots it overwrites.  The
+    /// device must already be awake.
+    fn warm_up<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<(), DrvError<E>> {
+        let mut mode = ModeReg(self.read(Register::Mode)?);
+        mode.set_mode(Mode::InternalTrigger as u8);
+        self.write(Register::Mode, mode.0)?;
+
+        let mut saved = [0u8; 3];
+        self.read_registers(Register::WaveformSequence0, &mut saved[1..])?;
+        saved[0] = Register::WaveformSequence0 as u8;
+
+        let timeout_ms = self.poll_timeout_ms;
+        let result = self
+            .set_single_effect(Effect::SmoothHumFive10)
+            .and_then(|_| self.set_go(true))
+            .and_then(|_| self.wait_until_idle(delay, timeout_ms));
+        let stopped = match result {
+            Ok(()) => Ok(()),
+            Err(_) => self.set_go(false),
+        };
+
+        let restored = self.write_registers(&saved);
+        result.and(stopped).and(restored)
+    }
+
+    /// Runs the diagnostic routine, which checks that an actuator is
+    /// connected and not shorted.  The mode register, including the standby
+    /// bit, is restored to its previous value afterwards.
+    /// Returns `DrvError::DeviceDiagnosticFailed` with the final status if
+    /// the actuator failed, or `DrvError::Timeout` if the routine did not
+    /// finish within the poll timeout.
+    pub fn run_diagnostics<D: DelayMs<u16>>(&mut self, delay: &mut D) -> Result<(), DrvError<E>> {
+        let previous = self.read(Register::Mode)?;
+        let mut mode = ModeReg(previous);
+        mode.set_standby(false);
+        mode.set_mode(Mode::Diagnostics as u8);
+        self.write(Register::Mode, mode.0)?;
+
+        let timeout_ms = self.poll_timeout_ms;
+        let result = self
+            .set_go(true)
+            .and_then(|_| self.wait_until_idle(delay, timeout_ms))
+            .and_then(|_| self.get_status());
+
+        self.write(Register::Mode, previous)?;
+        let status = result?;
+        if status.diagnostic_result() {
+            return Err(DrvError::DeviceDiagnosticFailed(status));
+        }
+        Ok(())
+    }
+
+    /// Starts auto calibration without waiting for it to finish, for use
+    /// from schedulers that can't block.  The calibration inputs are
+    /// programmed from `params` for the motor type currently selected in
+    /// the feedback control register.  Call `poll_calibration` until it
+    /// returns a result.
+    pub fn start_calibration(&mut self, params: &CalibrationParams) -> Result<(), DrvError<E>> {
+        let lra = FeedbackControlReg(self.read(Register::FeedbackControl)?).n_erm_lra();
+        self.set_calibration_params(params, lra)?;
+        self.start_routine(Mode::AutoCalibration)
+    }
+
+    /// Checks on a calibration started with `start_calibration`.  Returns
+    /// `None` while it is still running; once it has finished, the mode
+    /// register is restored and the results are returned, or
+    /// `DrvError::CalibrationFailed` if it did not converge.
+    pub fn poll_calibration(&mut self) -> Result<Option<LoadParams>, DrvError<E>> {
+        let status = match self.poll_routine()? {
+            Some(status) => status,
+            None => return Ok(None),
+        };
+        let passed = !status.diagnostic_result();
+        self.last_calibration = Some(passed);
+        if !passed {
+            return Err(DrvError::CalibrationFailed);
+        }
+        self.get_load_params().map(Some)
+    }
+
+    /// Starts the diagnostic routine without waiting for it to finish.
+    /// Call `poll_diagnostics` until it returns a result.
+    pub fn start_diagnostics(&mut self) -> Result<(), DrvError<E>> {
+        self.start_routine(Mode::Diagnostics)
+    }
+
+    /// Checks on diagnostics started with `start_diagnostics`.  Returns
+    /// `false` while they are still running and `true` once the actuator
+    /// has passed, after restoring the mode register.  A failure is
+    /// reported as `DrvError::DeviceDiagnosticFailed`.
+    pub fn poll_diagnostics(&mut self) -> Result<bool, DrvError<E>> {
+        match self.poll_routine()? {
+            Some(status) if status.diagnostic_result() => {
+                Err(DrvError::DeviceDiagnosticFailed(status))
+            }
+            Some(_) => Ok(true),
+            None => Ok(false),
+        }
+    }
+
+    /// Remembers the mode register, then wakes the device and fires the
+    /// routine selected by `mode`
+    fn start_routine(&mut self, mode: Mode) -> Result<(), DrvError<E>> {
+        let previous = self.read(Register::Mode)?;
+        let mut register = ModeReg(previous);
+        register.set_standby(false);
+        register.set_mode(mode as u8);
+        self.write(Register::Mode, register.0)?;
+        self.pending_mode = Some(previous);
+        self.set_go(true)
+    }
+
+    /// Returns `None` while the routine fired by `start_routine` is still
+    /// running; otherwise reads its status and restores the mode register.
+    fn poll_routine(&mut self) -> Result<Option<StatusReg>, DrvError<E>> {
+        if self.get_go()?.go() {
+            return Ok(None);
+        }
+        let status = self.get_status()?;
+        if let Some(previous) = self.pending_mode.take() {
+            self.write(Register::Mode, previous)?;
+        }
+        Ok(Some(status))
+    }
+
+    /// Heuristically checks whether the connected actuator matches the
+    /// motor type selected in the feedback control register.  Diagnostics
+    /// are run for the selected type; if they fail, they are run again with
+    /// the other type selected, and a pass in that case indicates a likely
+    /// mismatch.  The motor type is restored afterwards.
+    /// Returns false if the selected type passes, and the original
+    /// `DrvError::DeviceDiagnosticFailed` if neither does (for example
+    /// because no actuator is connected).
+    pub fn detect_motor_mismatch<D: DelayMs<u16>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<bool, DrvError<E>> {
+        let failure = match self.run_diagnostics(delay) {
+            Ok(()) => return Ok(false),
+            Err(failure @ DrvError::DeviceDiagnosticFailed(_)) => failure,
+            Err(err) => return Err(err),
+        };
+
+        let lra = FeedbackControlReg(self.read(Register::FeedbackControl)?).n_erm_lra();
+        self.set_feedback_motor_type(!lra)?;
+        let other = self.run_diagnostics(delay);
+        self.set_feedback_motor_type(lra)?;
+        match other {
+            Ok(()) => Ok(true),
+            Err(DrvError::DeviceDiagnosticFailed(_)) => Err(failure),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Factory provisioning in one call: programs the calibration inputs
+    /// from `params` for the motor type currently selected in the feedback
+    /// control register, checks the actuator with the diagnostic routine,
+    /// and only if that passes runs auto calibration.
+    /// Returns `DrvError::DeviceDiagnosticFailed` if the actuator is
+    /// missing or shorted, otherwise the result of `calibrate`.
+    pub fn provision<D: DelayMs<u16>>(
+        &mut self,
+        params: &CalibrationParams,
+        delay: &mut D,
+    ) -> Result<LoadParams, DrvError<E>> {
+        let lra = FeedbackControlReg(self.read(Register::FeedbackControl)?).n_erm_lra();
+        self.set_calibration_params(params, lra)?;
+        self.run_diagnostics(delay)?;
+        self.calibrate(delay)
+    }
+
+    /// Re-runs auto calibration at runtime, for example after the load on
+    /// the actuator has changed.  The calibration inputs are programmed
+    /// from `params` for the motor type currently selected in the feedback
+    /// control register, and the new results are returned.  As with
+    /// `calibrate`, the mode and standby state are restored afterwards
+    /// whether or not calibration succeeded.
+    pub fn recalibrate<D: DelayMs<u16>>(
+        &mut self,
+        params: &CalibrationParams,
+        delay: &mut D,
+    ) -> Result<LoadParams, DrvError<E>> {
+        let lra = FeedbackControlReg(self.read(Register::FeedbackControl)?).n_erm_lra();
+        self.set_calibration_params(params, lra)?;
+        self.calibrate(delay)
+    }
+
+    /// Returns whether the most recent `calibrate` call passed, or `None` if
+    /// calibration hasn't been run by this driver instance.  A call that
+    /// failed on the bus or timed out leaves this unchanged.  The device's
+    /// own result flag is shared with the diagnostics routine and clears
+    /// when read, so this is the only way to check on it later.
+    pub fn last_calibration_passed(&self) -> Option<bool> {
+        self.last_calibration
+    }
+
+    /// Reads the calibration results currently in use by the device
+    pub fn get_load_params(&mut self) -> Result<LoadParams, DrvError<E>> {
+        let mut buf = [0u8; 3];
+        self.read_registers(Register::AutoCalibrationCompensationResult, &mut buf)?;
+        Ok(LoadParams {
+            comp: buf[0],
+            bemf: buf[1],
+            gain: FeedbackControlReg(buf[2]).bemf_gain(),
+        })
+    }
+
+    /// Loads previously obtained calibration results into the device,
+    /// selecting the LRA or ERM feedback algorithm according to `lra`.
+    /// The results are only meaningful for the motor type they were
+    /// calibrated with, so this must match the `lra` that was passed to
+    /// `set_calibration_params` before calibrating.
+    pub fn set_load_params(&mut self, params: &LoadParams, lra: bool) -> Result<(), DrvError<E>> {
+        self.write(Register::AutoCalibrationCompensationResult, params.comp)?;
+        self.write(Register::AutoCalibrationBackEMFResult, params.bemf)?;
+        let mut feedback = FeedbackControlReg(self.read(Register::FeedbackControl)?);
+        feedback.set_n_erm_lra(lra);
+        feedback.set_bemf_gain(params.gain);
+        self.write(Register::FeedbackControl, feedback.0)
+    }
+
+    /// Loads `baseline` adjusted for the current temperature (see
+    /// `LoadParams::at_temperature`), keeping playback consistent as the
+    /// actuator warms up or cools down without re-running calibration.
+    pub fn apply_temperature_compensation(
+        &mut self,
+        baseline: &LoadParams,
+        temp_c: f32,
+        coeff: f32,
+    ) -> Result<(), DrvError<E>> {
+        let lra = FeedbackControlReg(self.read(Register::FeedbackControl)?).n_erm_lra();
+        self.set_load_params(&baseline.at_temperature(temp_c, coeff), lra)
+    }
+
+    /// Returns the raw measurement of the LRA resonance period from the
+    /// last time the actuator was driven in closed loop LRA mode.  The
+    /// period is `value × 98.46µs`.
+    pub fn get_lra_resonance_period(&mut self) -> Result<u8, DrvError<E>> {
+        self.read(Register::LRAResonancePeriod)
+    }
+
+    /// Returns the LRA resonance frequency in Hz, computed from the
+    /// resonance period register.  Returns `0.0` if no period has been
+    /// measured yet.
+    pub fn lra_resonance_hz(&mut self) -> Result<f32, DrvError<E>> {
+        let period = self.get_lra_resonance_period()?;
+        if period == 0 {
+            return Ok(0.0);
+        }
+        Ok(1_000_000.0 / (f32::from(period) * 98.46))
+    }
+
+    /// Sets OL_LRA_PERIOD[6:0], the drive period used when the LRA is
+    /// driven open loop (see `set_open_loop` and `Control3Reg::lra_open_loop`)
+    /// instead of tracking its resonance.  The period is
+    /// `period × 98.46µs`, so for example 43 drives at about 236 Hz.
+    /// Values above 127 are clamped.
+    pub fn set_lra_open_loop_period(&mut self, period: u8) -> Result<(), DrvError<E>> {
+        self.write(Register::LRAOpenLoopPeriod, period.min(0x7f))
+    }
+
+    /// Returns OL_LRA_PERIOD[6:0]; see `set_lra_open_loop_period`
+    pub fn get_lra_open_loop_period(&mut self) -> Result<u8, DrvError<E>> {
+        Ok(self.read(Register::LRAOpenLoopPeriod)? & 0x7f)
+    }
+
+    /// Returns the raw VBAT[7:0] supply voltage measurement; the supply is
+    /// `value × 5.6 V / 255`.  The device only samples the supply while it
+    /// is actively driving the actuator, so read this during playback (for
+    /// example during a strong click) to observe the supply under load.
+    pub fn vbat_raw(&mut self) -> Result<u8, DrvError<E>> {
+        self.read(Register::VBatVoltageMonitor)
+    }
+
+    /// Returns the supply voltage in volts; see `vbat_raw`
+    pub fn vbat_volts(&mut self) -> Result<f32, DrvError<E>> {
+        Ok(f32::from(self.vbat_raw()?) * 5.6 / 255.0)
+    }
+
+    /// Sets the audio-to-vibe peak detection time and low-pass filter
+    pub fn set_audio_to_vibe_control(
+        &mut self,
+        control: AudioToVibeControlReg,
+    ) -> Result<(), DrvError<E>> {
+        self.write(Register::AudioToVibeControl, control.0)
+    }
+
+    pub fn get_audio_to_vibe_control(&mut self) -> Result<AudioToVibeControlReg, DrvError<E>> {
+        self.read(Register::AudioToVibeControl)
+            .map(AudioToVibeControlReg)
+    }
+
+    /// This bit sets the minimum input level for audio-to-vibe; input below
+    /// it produces no vibration.
+    /// Input level (V) = ATH_MIN_INPUT[7:0] × 1.8 V / 255
+    pub fn set_audio_min_input(&mut self, level: u8) -> Result<(), DrvError<E>> {
+        self.write(Register::AudioToVibeMinInputLevel, level)
+    }
+
+    pub fn get_audio_min_input(&mut self) -> Result<u8, DrvError<E>> {
+        self.read(Register::AudioToVibeMinInputLevel)
+    }
+
+    /// This bit sets the input level at which audio-to-vibe reaches the
+    /// maximum output drive.
+    /// Input level (V) = ATH_MAX_INPUT[7:0] × 1.8 V / 255
+    pub fn set_audio_max_input(&mut self, level: u8) -> Result<(), DrvError<E>> {
+        self.write(Register::AudioToVibeMaxInputLevel, level)
+    }
+
+    pub fn get_audio_max_input(&mut self) -> Result<u8, DrvError<E>> {
+        self.read(Register::AudioToVibeMaxInputLevel)
+    }
+
+    /// This bit sets the output drive applied at the minimum input level.
+    /// Output drive (% of full scale) = ATH_MIN_DRIVE[7:0] / 255 × 100
+    pub fn set_audio_min_output(&mut self, drive: u8) -> Result<(), DrvError<E>> {
+        self.write(Register::AudioToVibeMinOutputDrive, drive)
+    }
+
+    pub fn get_audio_min_output(&mut self) -> Result<u8, DrvError<E>> {
+        self.read(Register::AudioToVibeMinOutputDrive)
+    }
+
+    /// This bit sets the output drive applied at and above the maximum
+    /// input level.
+    /// Output drive (% of full scale) = ATH_MAX_DRIVE[7:0] / 255 × 100
+    pub fn set_audio_max_output(&mut self, drive: u8) -> Result<(), DrvError<E>> {
+        self.write(Register::AudioToVibeMaxOutputDrive, drive)
+    }
+
+    pub fn get_audio_max_output(&mut self) -> Result<u8, DrvError<E>> {
+        self.read(Register::AudioToVibeMaxOutputDrive)
+    }
+
+    /// Configures the whole audio-to-vibe signal path and enters
+    /// `Mode::AudioToVibe`.
+    ///
+    /// `input_range_mv` is the (minimum, maximum) audio level in
+    /// millivolts, relative to the 1.8 V full scale of the analog input:
+    /// input below the minimum produces no vibration and input at or above
+    /// the maximum produces the maximum drive.  `output_range_percent` is
+    /// the (minimum, maximum) drive as a percentage of full scale.
+    /// The input is switched to `InputSignal::AcCoupledAnalog`, which the
+    /// audio-to-vibe mode requires.
+    pub fn configure_audio_to_vibe(
+        &mut self,
+        input_range_mv: (u16, u16),
+        output_range_percent: (u8, u8),
+        peak_time: PeakTime,
+        filter: LpFilter,
+    ) -> Result<(), DrvError<E>> {
+        let mv_to_level = |mv: u16| {
+            let mv = u32::from(mv.min(ANALOG_INPUT_FULL_SCALE_MV));
+            (mv * 255 / u32::from(ANALOG_INPUT_FULL_SCALE_MV)) as u8
+        };
+        let percent_to_level = |percent: u8| (u16::from(percent.min(100)) * 255 / 100) as u8;
+
+        let mut control = AudioToVibeControlReg(0);
+        control.set_peak_time(peak_time as u8);
+        control.set_filter(filter as u8);
+
+        let atv: [u8; 6] = [
+            Register::AudioToVibeControl as u8,
+            control.0,
+            mv_to_level(input_range_mv.0),
+            mv_to_level(input_range_mv.1),
+            percent_to_level(output_range_percent.0),
+            percent_to_level(output_range_percent.1),
+        ];
+        self.write_registers(&atv)?;
+
+        self.set_input_signal(InputSignal::AcCoupledAnalog)?;
+        self.check_input_config(Mode::AudioToVibe)?;
+        let mut mode = ModeReg(self.read(Register::Mode)?);
+        mode.set_standby(false);
+        mode.set_mode(Mode::AudioToVibe as u8);
+        self.write(Register::Mode, mode.0)
+    }
+
+    /// Reads back the audio-to-vibe settings, converting the levels to the
+    /// units used by `configure_audio_to_vibe`.  The conversion is lossy by
+    /// at most one register step (about 7 mV or 0.4%).
+    pub fn audio_to_vibe_config(&mut self) -> Result<AtvConfig, DrvError<E>> {
+        let mut buf = [0u8; 5];
+        self.read_registers(Register::AudioToVibeControl, &mut buf)?;
+        let level_to_mv =
+            |level: u8| (u32::from(level) * u32::from(ANALOG_INPUT_FULL_SCALE_MV) / 255) as u16;
+        let level_to_percent = |level: u8| (u16::from(level) * 100 / 255) as u8;
+        let control = AudioToVibeControlReg(buf[0]);
+        Ok(AtvConfig {
+            input_range_mv: (level_to_mv(buf[1]), level_to_mv(buf[2])),
+            output_range_percent: (level_to_percent(buf[3]), level_to_percent(buf[4])),
+            peak_time: control.peak_time(),
+            filter: control.filter(),
+        })
+    }
+
+    /// Reads back every register in one burst and compares it against
+    /// `expected`, returning every register that differs.  An empty
+    /// `ProfileDiff` means the device matches.
+    /// Note that reading the status register clears its latching flags.
+    pub fn verify_configuration(
+        &mut self,
+        expected: &DeviceProfile,
+    ) -> Result<ProfileDiff, DrvError<E>> {
+        let mut regs = [0u8; REGISTER_COUNT];
+        self.read_registers(Register::Status, &mut regs)?;
+        let mut diff = ProfileDiff {
+            mismatches: [None; REGISTER_COUNT],
+        };
+        for (address, (&actual, slot)) in regs.iter().zip(diff.mismatches.iter_mut()).enumerate() {
+            let register = match Register::from_address(address as u8) {
+                Some(register) => register,
+                None => continue,
+            };
+            if let Some(expected) = expected.expected(register) {
+                if actual != expected {
+                    *slot = Some(RegisterMismatch {
+                        register,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+        }
+        Ok(diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use self::std::vec::Vec;
+    use super::*;
+
+    /// An I2C bus backed by a register file, recording every write
+    struct MockBus {
+        regs: [u8; REGISTER_COUNT],
+        writes: Vec<Vec<u8>>,
+        /// When set, DEV_RESET never self-clears
+        stuck_in_reset: bool,
+    }
+
+    impl MockBus {
+        fn new() -> Self {
+            let mut bus = MockBus {
+                regs: [0; REGISTER_COUNT],
+                writes: Vec::new(),
+                stuck_in_reset: false,
+            };
+            bus.power_on();
+            bus
+        }
+
+        fn power_on(&mut self) {
+            for (address, reg) in self.regs.iter_mut().enumerate() {
+                *reg = Register::from_address(address as u8)
+                    .and_then(|register| register.default_value())
+                    .unwrap_or(0);
+            }
+        }
+    }
+
+    impl Write for MockBus {
+        type Error = ();
+
+        fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+            assert_eq!(address, ADDRESS);
+            self.writes.push(bytes.to_vec());
+            let start = bytes[0] as usize;
+            self.regs[start..start + bytes.len() - 1].copy_from_slice(&bytes[1..]);
+            if start == Register::Mode as usize && ModeReg(bytes[1]).dev_reset() {
+                if self.stuck_in_reset {
+                    return Ok(());
+                }
+                self.power_on();
+            }
+            Ok(())
+        }
+    }
+
+    impl WriteRead for MockBus {
+        type Error = ();
+
+        fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), ()> {
+            assert_eq!(address, ADDRESS);
+            let start = bytes[0] as usize;
+            buffer.copy_from_slice(&self.regs[start..start + buffer.len()]);
+            Ok(())
+        }
+    }
+
+    /// Counts the milliseconds it is asked to wait instead of waiting
+    struct MockDelay(u32);
+
+    impl DelayMs<u16> for MockDelay {
+        fn delay_ms(&mut self, ms: u16) {
+            self.0 += u32::from(ms);
+        }
+    }
+
+    #[test]
+    fn reset_returns_to_power_on_state() {
+        let mut haptic = Drv2605::new(MockBus::new());
+        haptic.set_standby(false).unwrap();
+        haptic.set_rtp_format(RtpFormat::Unsigned).unwrap();
+
+        let mut delay = MockDelay(0);
+        haptic.reset(&mut delay).unwrap();
+
+        let bus = haptic.release();
+        assert_eq!(bus.regs[Register::Mode as usize], ModeReg::default().0);
+        assert_eq!(
+            bus.regs[Register::Control3 as usize],
+            Register::Control3.default_value().unwrap()
+        );
+    }
+
+    #[test]
+    fn reset_times_out_if_dev_reset_never_clears() {
+        let mut bus = MockBus::new();
+        bus.stuck_in_reset = true;
+        let mut haptic = Drv2605::new(bus).with_poll_timeout_ms(25);
+
+        let mut delay = MockDelay(0);
+        assert!(matches!(haptic.reset(&mut delay), Err(DrvError::Timeout)));
+        assert_eq!(delay.0, 25);
+    }
+
+    #[test]
+    fn play_boosted_stops_playback_before_restoring_clamp() {
+        let mut haptic = Drv2605::new(MockBus::new());
+        assert!(matches!(
+            haptic.play_boosted(Effect::StrongClick100, 0x10, &mut MockDelay(0)),
+            Err(DrvError::InvalidConfig)
+        ));
+
+        // The mock never clears GO, so playback times out
+        let result = haptic.play_boosted(Effect::StrongClick100, 0xc0, &mut MockDelay(0));
+        assert!(matches!(result, Err(DrvError::Timeout)));
+
+        let bus = haptic.release();
+        assert_eq!(bus.regs[Register::Go as usize], 0);
+        assert_eq!(bus.regs[Register::OverdriveClampVoltage as usize], 0x8c);
+        assert!(bus
+            .writes
+            .iter()
+            .any(|w| w[..] == [Register::OverdriveClampVoltage as u8, 0xc0]));
+        let position = |bytes: [u8; 2]| bus.writes.iter().rposition(|w| w[..] == bytes).unwrap();
+        assert!(
+            position([Register::Go as u8, 0])
+                < position([Register::OverdriveClampVoltage as u8, 0x8c])
+        );
+    }
+
+    #[test]
+    fn set_waveform_serializes_waits_between_effects() {
+        let mut haptic = Drv2605::new(MockBus::new());
+        haptic
+            .set_waveform(&[
+                WaveformReg::new_effect(Effect::StrongClick100),
+                WaveformReg::new_wait_time(5),
+                WaveformReg::new_effect(Effect::StrongClick100),
+                WaveformReg::new_stop(),
+                WaveformReg::new_stop(),
+                WaveformReg::new_stop(),
+                WaveformReg::new_stop(),
+                WaveformReg::new_stop(),
+            ])
+            .unwrap();
+        assert_eq!(haptic.release().writes, [[0x04, 1, 0x85, 1, 0, 0, 0, 0, 0]]);
+    }
+
+    #[test]
+    fn set_sequence_entries_interleaves_waits() {
+        let mut haptic = Drv2605::new(MockBus::new());
+        haptic
+            .set_sequence_entries(&[
+                SequenceEntry::Effect(Effect::SharpClick100),
+                SequenceEntry::Wait(20),
+                SequenceEntry::Effect(Effect::SharpClick60),
+                SequenceEntry::Wait(200),
+                SequenceEntry::Effect(Effect::SharpClick30),
+            ])
+            .unwrap();
+        assert_eq!(
+            haptic.release().writes,
+            [[0x04, 4, 0x94, 5, 0xff, 6, 0, 0, 0]]
+        );
+    }
+
+    /// Worked examples of the Rated Voltage Programming equations, for a
+    /// 170 Hz LRA rated at 2.0 Vrms with the default 300 µs SAMPLE_TIME
+    /// and a 3.0 V average ERM with the default 4.8 ms drive time:
+    /// 2.0 × √(1 − (4 × 300 µs + 300 µs) × 170 Hz) / 20.58 mV = 83.88
+    /// 3.0 / (21.18 mV × (4.8 ms − 300 µs) / (4.8 ms + 75 µs + 75 µs)) = 155.81
+    #[test]
+    fn rated_voltage_matches_datasheet_equations() {
+        assert_eq!(lra_rated_voltage_reg(2.0, 170.0, 300.0), 84);
+        assert_eq!(erm_rated_voltage_reg(3.0, 4.8), 156);
+
+        // The power-on RATED_VOLTAGE of 0x3E is about 1.19 V for an ERM
+        assert!((erm_rated_voltage_volts(0x3e, 4.8) - 1.194).abs() < 0.005);
+        assert!((lra_rated_voltage_volts(84, 170.0, 300.0) - 2.003).abs() < 0.005);
+    }
+
+    /// Worked examples of the Overdrive Voltage-Clamp Programming
+    /// equations, for a 170 Hz LRA clamped at 3.0 V and an ERM clamped at
+    /// 3.6 V with the default 4.8 ms drive time:
+    /// 3.0 / (21.32 mV × √(1 − 170 Hz × 800 µs)) = 151.38
+    /// 3.6 / (21.64 mV × (4.8 ms − 300 µs) / (4.8 ms + 75 µs + 75 µs)) = 182.99
+    #[test]
+    fn overdrive_clamp_matches_datasheet_equations() {
+        assert_eq!(lra_overdrive_clamp_reg(3.0, 170.0), 151);
+        assert_eq!(erm_overdrive_clamp_reg(3.6, 4.8), 183);
+
+        // The power-on OD_CLAMP of 0x8C is about 2.75 V for an ERM
+        assert!((erm_overdrive_clamp_volts(0x8c, 4.8) - 2.754).abs() < 0.005);
+        assert!((lra_overdrive_clamp_volts(151, 170.0) - 2.992).abs() < 0.005);
+    }
+
+    #[test]
+    fn scale_effect_timing_sets_offsets_from_power_on() {
+        let mut haptic = Drv2605::new(MockBus::new());
+        haptic
+            .scale_effect_timing(Effect::Alert1000ms, 1.5)
+            .unwrap();
+
+        let bus = haptic.release();
+        let start = Register::OverdriveTimeOffset as usize;
+        assert_eq!(bus.regs[start..start + 4], [13, 38, 38, 13]);
+        assert_eq!(
+            bus.regs[Register::AudioToVibeControl as usize],
+            Register::AudioToVibeControl.default_value().unwrap()
+        );
+
+        let mut bus = MockBus::new();
+        bus.regs[Register::FeedbackControl as usize] |= 0x80;
+        let mut haptic = Drv2605::new(bus);
+        match haptic.scale_effect_timing(Effect::Alert1000ms, 1.5) {
+            Err(DrvError::InvalidConfig) => {}
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn verify_configuration_reports_every_mismatch() {
+        let mut haptic = Drv2605::new(MockBus::new());
+        haptic.set_standby(false).unwrap();
+
+        let profile = DeviceProfile::new()
+            .with_register(Register::Mode, ModeReg::default().0)
+            .with_register(Register::RatedVoltage, 0x3e)
+            .with_register(Register::OverdriveClampVoltage, 0x90);
+        let diff = haptic.verify_configuration(&profile).unwrap();
+
+        let mut mismatches = diff.iter();
+        assert_eq!(
+            mismatches.next(),
+            Some(&RegisterMismatch {
+                register: Register::Mode,
+                expected: 0x40,
+                actual: 0x00,
+            })
+        );
+        assert_eq!(
+            mismatches.next(),
+            Some(&RegisterMismatch {
+                register: Register::OverdriveClampVoltage,
+                expected: 0x90,
+                actual: 0x8c,
+            })
+        );
+        assert_eq!(mismatches.next(), None);
+    }
 }